@@ -0,0 +1,309 @@
+//! The recorded answers for each day and checking solutions against them.
+//!
+//! Answers are stored in a small subset of TOML, one table per day with a key per part:
+//!
+//! ```toml
+//! [day13]
+//! one = 724
+//! two = "CPJBERUL"
+//! ```
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Answer, Day, Error, Part};
+
+/// The file the answers are recorded in, relative to the crate root.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), Answer>,
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load answers from `path`, a missing file has no answers.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(contents.parse()?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the answers to `path` in the same format as they're loaded from. Only the answers are
+    /// kept, any comments in an existing file are lost.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Record `answer`, returning the previously recorded one if any.
+    pub fn insert(&mut self, day: u8, part: Part, answer: Answer) -> Option<Answer> {
+        self.answers.insert((day, part), answer)
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+fn part_key(part: Part) -> &'static str {
+    match part {
+        Part::One => "one",
+        Part::Two => "two",
+    }
+}
+
+/// `line` without a trailing `#` comment, a `#` inside a quoted string doesn't start one.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+pub(crate) fn parse_value(value: &str) -> Option<Answer> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let mut text = String::new();
+        let mut chars = quoted.chars();
+
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => text.push(chars.next().filter(|c| matches!(c, '"' | '\\'))?),
+                c => text.push(c),
+            }
+        }
+
+        return chars.as_str().is_empty().then_some(Answer::Text(text));
+    }
+
+    value.replace('_', "").parse().ok().map(Answer::Number)
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Self::new();
+        let mut day = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let invalid = |message: &str| {
+                Error::invalid_input(format!("{} on line {}: {}", message, index + 1, line))
+            };
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                day = Some(
+                    table
+                        .trim()
+                        .strip_prefix("day")
+                        .and_then(|d| d.parse::<u8>().ok())
+                        .ok_or_else(|| invalid("Expected a table like [day01]"))?,
+                );
+                continue;
+            }
+
+            let day = day.ok_or_else(|| invalid("Answer outside of a day"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("Expected key = value"))?;
+            let part = match key.trim() {
+                "one" => Part::One,
+                "two" => Part::Two,
+                _ => return Err(invalid("Expected the key to be one or two")),
+            };
+            let answer = parse_value(value.trim()).ok_or_else(|| invalid("Invalid answer"))?;
+
+            if answers.insert(day, part, answer).is_some() {
+                return Err(invalid("Duplicate answer"));
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut previous_day = None;
+
+        for ((day, part), answer) in &self.answers {
+            if previous_day != Some(*day) {
+                if previous_day.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{:02}]", day)?;
+                previous_day = Some(*day);
+            }
+
+            match answer {
+                Answer::Number(n) => writeln!(f, "{} = {}", part_key(*part), n)?,
+                Answer::Text(s) => writeln!(
+                    f,
+                    "{} = \"{}\"",
+                    part_key(*part),
+                    s.replace('\\', "\\\\").replace('"', "\\\"")
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The result of checking one part of a day against the recorded answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The answer matches the recorded one.
+    Pass(Answer),
+    /// The answer differs from the recorded one.
+    Fail { expected: Answer, actual: Answer },
+    /// There's no recorded answer to compare with.
+    Missing(Answer),
+    /// Solving failed, either with an error or a panic.
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass(answer) => write!(f, "pass {}", answer),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL expected {} got {}", expected, actual)
+            }
+            Outcome::Missing(answer) => write!(f, "missing {}", answer),
+            Outcome::Error(message) => write!(f, "ERROR {}", message),
+        }
+    }
+}
+
+fn solve(day: &Day, part: Part, input: &str) -> Result<Answer, String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| day.solve(part, input))) {
+        Ok(result) => result.map_err(|e: Error| e.to_string()),
+        Err(payload) => Err(payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .map(|message| format!("panicked: {}", message))
+            .unwrap_or_else(|| "panicked".to_owned())),
+    }
+}
+
+/// Solve one part of `day` and compare it with the recorded answer.
+pub fn check(day: &Day, part: Part, input: &str, answers: &Answers) -> Outcome {
+    let actual = match solve(day, part, input) {
+        Ok(actual) => actual,
+        Err(message) => return Outcome::Error(message),
+    };
+
+    match answers.get(day.number, part) {
+        None => Outcome::Missing(actual),
+        Some(expected) if *expected == actual => Outcome::Pass(actual),
+        Some(expected) => Outcome::Fail {
+            expected: expected.clone(),
+            actual,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Answers, Outcome};
+    use crate::{find_day, Answer, Part};
+
+    const ANSWERS: &str = r#"# Recorded answers
+[day01]
+one = 7
+two = 5 # trailing comment
+
+[day13]
+one = 1_024
+two = "A \"B\" \\ # C" # comment after a string
+"#;
+
+    #[test]
+    fn test_parse_and_display() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+
+        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.get(1, Part::Two), Some(&Answer::Number(5)));
+        assert_eq!(answers.get(13, Part::One), Some(&Answer::Number(1024)));
+        assert_eq!(
+            answers.get(13, Part::Two),
+            Some(&Answer::Text(r#"A "B" \ # C"#.to_owned()))
+        );
+        assert_eq!(answers.get(2, Part::One), None);
+        assert_eq!(answers.to_string().parse(), Ok(answers));
+
+        assert!("one = 1".parse::<Answers>().is_err());
+        assert!("[day1]\nthree = 1".parse::<Answers>().is_err());
+        assert!("[day1]\none = 1\none = 2".parse::<Answers>().is_err());
+        assert!("[day1]\none = \"x".parse::<Answers>().is_err());
+        assert!("[first]".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let day = find_day(1).unwrap();
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        let mut answers: Answers = ANSWERS.parse().unwrap();
+
+        assert_eq!(
+            check(day, Part::One, input, &answers),
+            Outcome::Pass(Answer::Number(7))
+        );
+        assert_eq!(
+            check(day, Part::Two, input, &answers),
+            Outcome::Pass(Answer::Number(5))
+        );
+
+        answers.insert(1, Part::One, Answer::Number(8));
+        assert_eq!(
+            check(day, Part::One, input, &answers),
+            Outcome::Fail {
+                expected: Answer::Number(8),
+                actual: Answer::Number(7)
+            }
+        );
+        assert_eq!(
+            check(day, Part::One, input, &Answers::new()),
+            Outcome::Missing(Answer::Number(7))
+        );
+        assert!(matches!(
+            check(day, Part::One, "x", &answers),
+            Outcome::Error(_)
+        ));
+    }
+}
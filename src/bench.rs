@@ -0,0 +1,275 @@
+//! Benchmarking of each day, timing parsing separately from solving both parts.
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{Error, Solution};
+
+/// How many times to run each phase.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Untimed runs before measuring.
+    pub warmup: u32,
+    /// Timed runs, at least one is always made.
+    pub iterations: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            iterations: 10,
+        }
+    }
+}
+
+/// Summary statistics over the timed runs of a phase.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: u32,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, which must not be empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "Can't summarise zero samples");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        Self {
+            runs: sorted.len() as u32,
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+        }
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"runs":{},"min_ns":{},"median_ns":{},"mean_ns":{}}}"#,
+            self.runs,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos()
+        )
+    }
+}
+
+/// Time `f` according to `config`.
+pub fn measure<T>(config: &Config, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+
+    let samples: Vec<_> = (0..config.iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(&samples)
+}
+
+/// The timings of a single day.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: Stats,
+    pub one: Stats,
+    pub two: Stats,
+}
+
+impl DayBench {
+    fn phases(&self) -> [(&'static str, Stats); 3] {
+        [
+            ("parse", self.parse),
+            ("part 1", self.one),
+            ("part 2", self.two),
+        ]
+    }
+}
+
+/// Benchmark a [`Solution`], solving once up front so that errors are reported before timing.
+pub(crate) fn bench_solution<S: Solution>(
+    day: u8,
+    input: &str,
+    config: &Config,
+) -> Result<DayBench, Error> {
+    let parsed = S::parse(input)?;
+    S::star_one(&parsed)?;
+    S::star_two(&parsed)?;
+
+    Ok(DayBench {
+        day,
+        parse: measure(config, || S::parse(input)),
+        one: measure(config, || S::star_one(&parsed)),
+        two: measure(config, || S::star_two(&parsed)),
+    })
+}
+
+/// The benchmarks of several days, displayed as a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub days: Vec<DayBench>,
+}
+
+impl Report {
+    /// The sum of the median time of every phase of every day.
+    pub fn total_median(&self) -> Duration {
+        self.days
+            .iter()
+            .flat_map(|d| d.phases())
+            .map(|(_, stats)| stats.median)
+            .sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        let days: Vec<_> = self
+            .days
+            .iter()
+            .map(|d| {
+                format!(
+                    r#"{{"day":{},"parse":{},"one":{},"two":{}}}"#,
+                    d.day,
+                    d.parse.to_json(),
+                    d.one.to_json(),
+                    d.two.to_json()
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"days":[{}],"total_median_ns":{}}}"#,
+            days.join(","),
+            self.total_median().as_nanos()
+        )
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}",
+            "Day", "Phase", "Min", "Median", "Mean"
+        )?;
+
+        for day in &self.days {
+            for (phase, stats) in day.phases() {
+                writeln!(
+                    f,
+                    "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}",
+                    day.day,
+                    phase,
+                    format!("{:.3?}", stats.min),
+                    format!("{:.3?}", stats.median),
+                    format!("{:.3?}", stats.mean)
+                )?;
+            }
+        }
+
+        write!(f, "Total of medians: {:.3?}", self.total_median())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{measure, Config, Report, Stats};
+    use crate::find_day;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            Stats::from_samples(&[ms(5), ms(1), ms(3)]),
+            Stats {
+                runs: 3,
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3)
+            }
+        );
+        assert_eq!(
+            Stats::from_samples(&[ms(4), ms(1), ms(2), ms(9)]),
+            Stats {
+                runs: 4,
+                min: ms(1),
+                median: ms(3),
+                mean: ms(4)
+            }
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let stats = measure(
+            &Config {
+                warmup: 2,
+                iterations: 5,
+            },
+            || calls += 1,
+        );
+
+        assert_eq!(calls, 7);
+        assert_eq!(stats.runs, 5);
+
+        // At least one timed run is always made
+        assert_eq!(
+            measure(
+                &Config {
+                    warmup: 0,
+                    iterations: 0
+                },
+                || ()
+            )
+            .runs,
+            1
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let config = Config {
+            warmup: 0,
+            iterations: 3,
+        };
+        let day = find_day(1).unwrap();
+        let report = Report {
+            days: vec![day.bench("199\n200\n208\n210", &config).unwrap()],
+        };
+
+        let text = report.to_string();
+        assert_eq!(text.lines().count(), 5);
+        assert!(text
+            .lines()
+            .nth(2)
+            .unwrap()
+            .trim_start()
+            .starts_with("1  part 1"));
+
+        let json = report.to_json();
+        assert!(json.starts_with(r#"{"days":[{"day":1,"parse":{"runs":3,"min_ns":"#));
+        assert!(json.ends_with(&format!(
+            r#""total_median_ns":{}}}"#,
+            report.total_median().as_nanos()
+        )));
+
+        assert!(day.bench("x", &config).is_err());
+    }
+}
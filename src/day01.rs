@@ -1,4 +1,4 @@
-use crate::parse_lines;
+use crate::{try_parse_lines, Error, Solution};
 
 fn count_increments(readings: impl IntoIterator<Item = u64>) -> u64 {
     readings
@@ -13,38 +13,32 @@ fn count_increments(readings: impl IntoIterator<Item = u64>) -> u64 {
         .0
 }
 
-pub fn star_one(input: &str) -> u64 {
-    count_increments(parse_lines::<u64>(input))
-}
+pub struct Day01;
 
-pub fn star_two(input: &str) -> u64 {
-    let readings: Vec<u64> = parse_lines(input).collect();
+impl Solution for Day01 {
+    type Input = Vec<u64>;
+    type One = u64;
+    type Two = u64;
 
-    count_increments(readings.windows(3).map(|readings| readings.iter().sum()))
-}
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_lines(input).collect::<Result<_, _>>()?)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"199
-200
-208
-210
-200
-207
-240
-269
-260
-263
-"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 7);
+    fn star_one(readings: &Self::Input) -> Result<Self::One, Error> {
+        Ok(count_increments(readings.iter().copied()))
     }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 5);
+    fn star_two(readings: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(count_increments(
+            readings.windows(3).map(|readings| readings.iter().sum()),
+        ))
     }
 }
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day01::star_one(&Day01::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day01::star_two(&Day01::parse(input)?)
+}
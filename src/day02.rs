@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::parse_lines;
+use crate::{try_parse_lines, Error, Solution};
 
 #[derive(Debug)]
 enum Direction {
@@ -10,89 +10,89 @@ enum Direction {
 }
 
 impl FromStr for Direction {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
             "forward" => Ok(Direction::Forward),
             "down" => Ok(Direction::Down),
             "up" => Ok(Direction::Up),
-            _ => Err(format!("Invalid direction {}", s)),
+            _ => Err(Error::invalid_input(format!("Invalid direction {}", s))),
         }
     }
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     direction: Direction,
     units: i64,
 }
 
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.trim().split_whitespace();
+        let mut parts = s.split_whitespace();
 
         let direction: Direction = match parts.next().map(str::trim) {
             Some(s) => s.parse()?,
-            _ => return Err(format!("Invalid instruction {}", s)),
+            _ => return Err(Error::invalid_input(format!("Invalid instruction {}", s))),
         };
 
         let units = match parts.next().map(str::trim).map(str::parse) {
             Some(Ok(units)) => units,
-            _ => return Err(format!("Invalid instruction {}", s)),
+            _ => return Err(Error::invalid_input(format!("Invalid instruction {}", s))),
         };
 
         Ok(Instruction { direction, units })
     }
 }
 
-pub fn star_one(input: &str) -> i64 {
-    let instructions = parse_lines::<Instruction>(input);
+pub struct Day02;
 
-    let final_pos = instructions.fold((0, 0), |pos, instruction| match instruction.direction {
-        Direction::Forward => (pos.0 + instruction.units, pos.1),
-        Direction::Down => (pos.0, pos.1 + instruction.units),
-        Direction::Up => (pos.0, pos.1 - instruction.units),
-    });
+impl Solution for Day02 {
+    type Input = Vec<Instruction>;
+    type One = i64;
+    type Two = i64;
 
-    final_pos.0 * final_pos.1
-}
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_lines(input).collect::<Result<_, _>>()?)
+    }
 
-pub fn star_two(input: &str) -> i64 {
-    let instructions = parse_lines::<Instruction>(input);
+    fn star_one(instructions: &Self::Input) -> Result<Self::One, Error> {
+        let final_pos = instructions
+            .iter()
+            .fold((0, 0), |pos, instruction| match instruction.direction {
+                Direction::Forward => (pos.0 + instruction.units, pos.1),
+                Direction::Down => (pos.0, pos.1 + instruction.units),
+                Direction::Up => (pos.0, pos.1 - instruction.units),
+            });
 
-    let final_pos = instructions.fold((0, 0, 0), |pos, instruction| match instruction.direction {
-        Direction::Forward => (
-            pos.0 + instruction.units,
-            pos.1 + instruction.units * pos.2,
-            pos.2,
-        ),
-        Direction::Down => (pos.0, pos.1, pos.2 + instruction.units),
-        Direction::Up => (pos.0, pos.1, pos.2 - instruction.units),
-    });
+        Ok(final_pos.0 * final_pos.1)
+    }
 
-    final_pos.0 * final_pos.1
+    fn star_two(instructions: &Self::Input) -> Result<Self::Two, Error> {
+        let final_pos =
+            instructions
+                .iter()
+                .fold((0, 0, 0), |pos, instruction| match instruction.direction {
+                    Direction::Forward => (
+                        pos.0 + instruction.units,
+                        pos.1 + instruction.units * pos.2,
+                        pos.2,
+                    ),
+                    Direction::Down => (pos.0, pos.1, pos.2 + instruction.units),
+                    Direction::Up => (pos.0, pos.1, pos.2 - instruction.units),
+                });
+
+        Ok(final_pos.0 * final_pos.1)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"forward 5
-down 5
-forward 8
-up 3
-down 8
-forward 2"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 150);
-    }
+pub fn star_one(input: &str) -> Result<i64, Error> {
+    Day02::star_one(&Day02::parse(input)?)
+}
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 900);
-    }
+pub fn star_two(input: &str) -> Result<i64, Error> {
+    Day02::star_two(&Day02::parse(input)?)
 }
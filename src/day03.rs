@@ -1,3 +1,5 @@
+use crate::{Error, Solution};
+
 const fn mask(n: usize) -> u64 {
     !(!0_u64 << n)
 }
@@ -9,13 +11,15 @@ enum Mode {
 }
 
 fn find_bit(x: usize, data: &[Vec<char>], mode: Mode) -> u8 {
-    let (ones, zeroes) = (0..data.len()).fold((0, 0), |(count_ones, count_zeroes), y| {
-        if data[y][x] == '1' {
-            (count_ones + 1, count_zeroes)
-        } else {
-            (count_ones, count_zeroes + 1)
-        }
-    });
+    let (ones, zeroes) = data
+        .iter()
+        .fold((0, 0), |(count_ones, count_zeroes), row| {
+            if row[x] == '1' {
+                (count_ones + 1, count_zeroes)
+            } else {
+                (count_ones, count_zeroes + 1)
+            }
+        });
 
     match mode {
         Mode::MostCommon => (ones >= zeroes) as u8,
@@ -23,14 +27,21 @@ fn find_bit(x: usize, data: &[Vec<char>], mode: Mode) -> u8 {
     }
 }
 
-fn find_rating(mut data: Vec<Vec<char>>, mode: Mode) -> u64 {
+fn find_rating(mut data: Vec<Vec<char>>, mode: Mode) -> Result<u64, Error> {
+    let length = data[0].len();
     let mut x = 0;
 
     while data.len() > 1 {
+        if x == length {
+            return Err(Error::no_solution(
+                "Multiple numbers remain after considering every bit",
+            ));
+        }
+
         let bit = find_bit(x, &data, mode);
         let mut should_retain = data
             .iter()
-            .map(|row| row[x] == '1' && bit == 1 || row[x] == '0' && bit == 0)
+            .map(|row| (row[x] == '1' && bit == 1) || (row[x] == '0' && bit == 0))
             .collect::<Vec<_>>()
             .into_iter();
 
@@ -39,74 +50,75 @@ fn find_rating(mut data: Vec<Vec<char>>, mode: Mode) -> u64 {
         x += 1;
     }
 
-    let length = data[0].len();
-    data[0].iter().enumerate().fold(0_u64, |acc, (idx, bit)| {
+    Ok(data[0].iter().enumerate().fold(0_u64, |acc, (idx, bit)| {
         let numeric_value = match bit {
             '1' => 1,
             '0' => 0,
-            _ => unreachable!(),
+            _ => unreachable!("Bits are validated when parsing"),
         };
 
-        acc | (numeric_value << length - idx - 1)
-    })
+        acc | (numeric_value << (length - idx - 1))
+    }))
 }
 
-pub fn star_one(input: &str) -> u64 {
-    let data: Vec<Vec<char>> = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .map(|l| l.chars().collect())
-        .collect();
+pub struct Day03;
 
-    let number_length = data[0].len();
-    let gamma_rate = (0..number_length).fold(0_u64, |acc, x| {
-        let bit = find_bit(x, &data, Mode::MostCommon);
+impl Solution for Day03 {
+    type Input = Vec<Vec<char>>;
+    type One = u64;
+    type Two = u64;
 
-        acc | ((bit as u64) << (number_length - x - 1))
-    });
-    let epsilon_rate = (gamma_rate ^ u64::MAX) & mask(number_length);
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let data: Vec<Vec<char>> = input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().collect())
+            .collect();
 
-    gamma_rate * epsilon_rate
-}
+        let length = match data.first() {
+            Some(row) => row.len(),
+            None => return Err(Error::invalid_input("No numbers in input")),
+        };
+
+        if let Some(row) = data
+            .iter()
+            .find(|row| row.len() != length || row.iter().any(|c| *c != '0' && *c != '1'))
+        {
+            return Err(Error::invalid_input(format!(
+                "Expected a {} bit binary number, got `{}`",
+                length,
+                row.iter().collect::<String>()
+            )));
+        }
 
-pub fn star_two(input: &str) -> u64 {
-    let data: Vec<Vec<char>> = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .map(|l| l.chars().collect())
-        .collect();
+        Ok(data)
+    }
 
-    let oxygen_rating = find_rating(data.clone(), Mode::MostCommon);
-    let co2_scrubber_rating = find_rating(data.clone(), Mode::LeastCommon);
+    fn star_one(data: &Self::Input) -> Result<Self::One, Error> {
+        let number_length = data[0].len();
+        let gamma_rate = (0..number_length).fold(0_u64, |acc, x| {
+            let bit = find_bit(x, data, Mode::MostCommon);
 
-    oxygen_rating * co2_scrubber_rating
-}
+            acc | ((bit as u64) << (number_length - x - 1))
+        });
+        let epsilon_rate = (gamma_rate ^ u64::MAX) & mask(number_length);
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"00100
-11110
-10110
-10111
-10101
-01111
-00111
-11100
-10000
-11001
-00010
-01010"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 198);
+        Ok(gamma_rate * epsilon_rate)
     }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 230);
+    fn star_two(data: &Self::Input) -> Result<Self::Two, Error> {
+        let oxygen_rating = find_rating(data.clone(), Mode::MostCommon)?;
+        let co2_scrubber_rating = find_rating(data.clone(), Mode::LeastCommon)?;
+
+        Ok(oxygen_rating * co2_scrubber_rating)
     }
 }
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day03::star_one(&Day03::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day03::star_two(&Day03::parse(input)?)
+}
@@ -1,7 +1,7 @@
 use core::fmt;
 use std::str::FromStr;
 
-use crate::parse_custom_separated;
+use crate::{try_parse_custom_separated, try_parse_whitespace_separated, Error, Solution};
 
 #[derive(Debug, Clone)]
 struct Entry {
@@ -19,7 +19,7 @@ impl From<u64> for Entry {
 }
 
 #[derive(Debug, Clone)]
-struct Board {
+pub struct Board {
     rows: Vec<Vec<Entry>>,
 }
 
@@ -62,7 +62,7 @@ impl fmt::Display for Board {
                 }
             }
 
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -70,22 +70,26 @@ impl fmt::Display for Board {
 }
 
 impl FromStr for Board {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let rows: Vec<Vec<Entry>> = s
             .lines()
             .map(str::trim)
-            .filter(|l| l.len() > 0)
+            .filter(|l| !l.is_empty())
             .map(|line| {
-                line.split_whitespace()
-                    .map(|n| n.parse::<u64>().expect("Should be parasable number"))
-                    .map(From::from)
-                    .collect()
+                try_parse_whitespace_separated::<u64>(line)
+                    .map(|n| n.map(From::from))
+                    .collect::<Result<_, _>>()
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        assert!(rows.len() == 5 && rows.iter().all(|row| row.len() == 5));
+        if rows.len() != 5 || rows.iter().any(|row| row.len() != 5) {
+            return Err(Error::invalid_input(format!(
+                "Expected a 5x5 board, got:\n{}",
+                s
+            )));
+        }
 
         Ok(Self { rows })
     }
@@ -95,7 +99,7 @@ fn play(
     draws: impl IntoIterator<Item = u64>,
     boards: &mut [Board],
     first_win: bool,
-) -> (Board, u64) {
+) -> Result<(Board, u64), Error> {
     let mut won_boards = 0;
     let board_count = boards.len();
 
@@ -109,89 +113,70 @@ fn play(
 
             if board.is_win() {
                 if first_win {
-                    return (board.clone(), draw);
+                    return Ok((board.clone(), draw));
                 } else {
                     won_boards += 1;
 
                     if won_boards == board_count {
-                        return (board.clone(), draw);
+                        return Ok((board.clone(), draw));
                     }
                 }
             }
         }
     }
 
-    unreachable!()
+    Err(Error::no_solution("Ran out of draws before the game ended"))
 }
 
-pub fn star_one(input: &str) -> u64 {
-    let draws = parse_custom_separated::<u64>(input.lines().nth(0).unwrap(), ",");
-
-    let mut boards: Vec<Board> = input
-        .split("\n\n")
-        .skip(1)
-        .map(FromStr::from_str)
-        .collect::<Result<Vec<_>, String>>()
-        .expect("Failed to parse boards");
-
-    let (winning_board, last_draw) = play(draws, &mut boards, true);
-
-    winning_board
+fn unmarked_score(board: &Board, last_draw: u64) -> u64 {
+    board
         .all_entries()
-        .filter_map(|e| (!e.marked).then(|| e.number))
+        .filter(|e| !e.marked)
+        .map(|e| e.number)
         .sum::<u64>()
         * last_draw
 }
 
-pub fn star_two(input: &str) -> u64 {
-    let draws = parse_custom_separated::<u64>(input.lines().nth(0).unwrap(), ",");
+pub struct Day04;
 
-    let mut boards: Vec<Board> = input
-        .split("\n\n")
-        .skip(1)
-        .map(FromStr::from_str)
-        .collect::<Result<Vec<_>, String>>()
-        .expect("Failed to parse boards");
+impl Solution for Day04 {
+    type Input = (Vec<u64>, Vec<Board>);
+    type One = u64;
+    type Two = u64;
 
-    let (winning_board, last_draw) = play(draws, &mut boards, false);
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let draws = match input.lines().next() {
+            Some(line) => try_parse_custom_separated::<u64>(line, ",").collect::<Result<_, _>>()?,
+            None => return Err(Error::invalid_input("Missing draws")),
+        };
 
-    winning_board
-        .all_entries()
-        .filter_map(|e| (!e.marked).then(|| e.number))
-        .sum::<u64>()
-        * last_draw
-}
+        let boards: Vec<Board> = input
+            .split("\n\n")
+            .skip(1)
+            .map(FromStr::from_str)
+            .collect::<Result<_, _>>()?;
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-22 13 17 11  0
- 8  2 23  4 24
-21  9 14 16  7
- 6 10  3 18  5
- 1 12 20 15 19
-
- 3 15  0  2 22
- 9 18 13 17  5
-19  8  7 25 23
-20 11 10 24  4
-14 21 16 12  6
-
-14 21 17 24  4
-10 16 15  9 19
-18  8 23 26 20
-22 11 13  6  5
- 2  0 12  3  7"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 4512);
+        Ok((draws, boards))
     }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 1924);
+    fn star_one((draws, boards): &Self::Input) -> Result<Self::One, Error> {
+        let (winning_board, last_draw) = play(draws.iter().copied(), &mut boards.clone(), true)?;
+
+        Ok(unmarked_score(&winning_board, last_draw))
+    }
+
+    fn star_two((draws, boards): &Self::Input) -> Result<Self::Two, Error> {
+        let (winning_board, last_draw) =
+            play(draws.iter().copied(), &mut boards.clone(), false)?;
+
+        Ok(unmarked_score(&winning_board, last_draw))
     }
 }
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day04::star_one(&Day04::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day04::star_two(&Day04::parse(input)?)
+}
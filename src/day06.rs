@@ -1,6 +1,6 @@
 use std::mem;
 
-use crate::parse_custom_separated;
+use crate::{try_parse_custom_separated, Error, Solution};
 
 fn tick(fishes: &mut [usize], new_fishes: &mut [usize]) {
     for key in 0..=8 {
@@ -13,16 +13,8 @@ fn tick(fishes: &mut [usize], new_fishes: &mut [usize]) {
     }
 }
 
-fn run(input: &str, tick_count: usize) -> usize {
-    let mut fish: [usize; 9] = {
-        let mut result: [usize; 9] = [0; 9];
-
-        for timer in parse_custom_separated::<usize>(input, ",") {
-            result[timer] += 1;
-        }
-
-        result
-    };
+fn run(initial: &[usize; 9], tick_count: usize) -> usize {
+    let mut fish: [usize; 9] = *initial;
     let mut new_fishes: [usize; 9] = [0; 9];
 
     for _ in 0..tick_count {
@@ -31,33 +23,53 @@ fn run(input: &str, tick_count: usize) -> usize {
         mem::swap(&mut fish, &mut new_fishes);
     }
 
-    if tick_count % 2 == 0 {
+    if tick_count.is_multiple_of(2) {
         fish.into_iter().sum()
     } else {
         new_fishes.into_iter().sum()
     }
 }
 
-pub fn star_one(input: &str) -> usize {
-    run(input, 80)
-}
+pub struct Day06;
 
-pub fn star_two(input: &str) -> usize {
-    run(input, 256)
-}
+impl Solution for Day06 {
+    type Input = [usize; 9];
+    type One = usize;
+    type Two = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut result: [usize; 9] = [0; 9];
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = "3,4,3,1,2";
+        for timer in try_parse_custom_separated::<usize>(input, ",") {
+            let timer = timer?;
 
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 5934);
+            match result.get_mut(timer) {
+                Some(count) => *count += 1,
+                None => {
+                    return Err(Error::invalid_input(format!(
+                        "Invalid timer {}, expected 0 through 8",
+                        timer
+                    )))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn star_one(fish: &Self::Input) -> Result<Self::One, Error> {
+        Ok(run(fish, 80))
     }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 26984457539);
+    fn star_two(fish: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(run(fish, 256))
     }
 }
+
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day06::star_one(&Day06::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day06::star_two(&Day06::parse(input)?)
+}
@@ -1,53 +1,54 @@
-use crate::parse_custom_separated;
+use crate::{try_parse_custom_separated, Error, Solution};
 
-fn best_fuel_cost<F>(positions: &[i64], mut calc: F) -> i64
+fn best_fuel_cost<F>(positions: &[i64], mut calc: F) -> Result<i64, Error>
 where
     F: FnMut(i64) -> i64,
 {
-    let (max, min) = (
-        *positions.iter().max().unwrap(),
-        *positions.iter().min().unwrap(),
-    );
+    let (max, min) = match (positions.iter().max(), positions.iter().min()) {
+        (Some(max), Some(min)) => (*max, *min),
+        _ => return Err(Error::invalid_input("No crab positions")),
+    };
 
     let (_, fuel) = (min..=max)
         .map(|o| (o, positions.iter().map(|p| calc((p - o).abs())).sum()))
         .min_by_key(|(_, fuel)| *fuel)
-        .unwrap();
+        .expect("There's at least one position when min and max exist");
 
-    fuel
+    Ok(fuel)
 }
 
 fn identity<T>(x: T) -> T {
     x
 }
 
-pub fn star_one(input: &str) -> i64 {
-    let positions: Vec<_> = parse_custom_separated::<i64>(input, ",").collect();
+pub struct Day07;
 
-    best_fuel_cost(&positions, identity)
-}
+impl Solution for Day07 {
+    type Input = Vec<i64>;
+    type One = i64;
+    type Two = i64;
 
-pub fn star_two(input: &str) -> i64 {
-    let positions: Vec<_> = parse_custom_separated::<i64>(input, ",").collect();
-    fn fuel_cost(moves: i64) -> i64 {
-        (moves * (moves + 1)) / 2
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_custom_separated::<i64>(input, ",").collect::<Result<_, _>>()?)
     }
 
-    best_fuel_cost(&positions, fuel_cost)
-}
+    fn star_one(positions: &Self::Input) -> Result<Self::One, Error> {
+        best_fuel_cost(positions, identity)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = "16,1,2,0,4,2,7,1,2,14";
+    fn star_two(positions: &Self::Input) -> Result<Self::Two, Error> {
+        fn fuel_cost(moves: i64) -> i64 {
+            (moves * (moves + 1)) / 2
+        }
 
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 37);
+        best_fuel_cost(positions, fuel_cost)
     }
+}
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 168);
-    }
+pub fn star_one(input: &str) -> Result<i64, Error> {
+    Day07::star_one(&Day07::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<i64, Error> {
+    Day07::star_two(&Day07::parse(input)?)
 }
@@ -1,5 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::{DigitIterator, Error, Solution};
+
 #[derive(Debug, Clone)]
 struct Digit {
     value: usize,
@@ -57,7 +59,7 @@ fn sorted_chars(chars: impl IntoIterator<Item = char>) -> Vec<char> {
     result
 }
 
-fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
+fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> Result<usize, Error> {
     let all_digits = Digit::all();
 
     let segment_map: HashMap<usize, Vec<Digit>> = {
@@ -78,7 +80,7 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
             p.iter()
                 .filter(|char| char.is_ascii() && char.is_ascii_lowercase())
         })
-        .map(|c| *c)
+        .copied()
         .collect();
 
     let mut candidates: HashMap<char, HashSet<char>> =
@@ -89,11 +91,18 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
         .filter_map(|pattern| {
             segment_map
                 .get(&pattern.len())
-                .and_then(|c| (c.len() == 1).then(|| (pattern, &c[0])))
+                .and_then(|c| (c.len() == 1).then_some((pattern, &c[0])))
         })
         .collect();
     uniques.sort_by_key(|(_, u)| u.segments.len());
 
+    if uniques.len() < 3 {
+        return Err(Error::invalid_input(format!(
+            "Expected the patterns for 1, 4, and 7 in {:?}",
+            segments
+        )));
+    }
+
     // Unique lengths
     // (2, 1), // 1
     // (4, 4), // 4
@@ -112,7 +121,7 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
         .filter_map(|pattern| {
             segment_map
                 .get(&pattern.len())
-                .and_then(|c| (c[0].segments.len() == 6).then(|| (pattern, c)))
+                .and_then(|c| (c[0].segments.len() == 6).then_some((pattern, c)))
         })
         .collect();
 
@@ -124,10 +133,17 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
         .filter_map(|pattern| {
             segment_map
                 .get(&pattern.len())
-                .and_then(|c| (c[0].segments.len() == 5).then(|| (pattern, c)))
+                .and_then(|c| (c[0].segments.len() == 5).then_some((pattern, c)))
         })
         .collect();
 
+    if length_five.is_empty() || length_six.is_empty() {
+        return Err(Error::invalid_input(format!(
+            "Expected patterns with five and six segments in {:?}",
+            segments
+        )));
+    }
+
     let five_intersection = length_five
         .iter()
         .fold(length_five[0].0.clone(), |acc, (chars, _)| {
@@ -151,7 +167,7 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
     candidates.insert('c', one.0.difference(&candidates[&'f']).cloned().collect());
 
     {
-        let g: HashSet<_> = five_intersection.difference(&seven.0).cloned().collect();
+        let g: HashSet<_> = five_intersection.difference(seven.0).cloned().collect();
         let g: HashSet<_> = g.difference(&candidates[&'d']).cloned().collect();
         let g = g.difference(&candidates[&'a']).copied().collect();
 
@@ -159,7 +175,7 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
     }
 
     {
-        let b: HashSet<_> = four.0.difference(&one.0).cloned().collect();
+        let b: HashSet<_> = four.0.difference(one.0).cloned().collect();
         let b: HashSet<_> = b.difference(&candidates[&'d']).cloned().collect();
 
         candidates.insert('b', b);
@@ -167,17 +183,23 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
     {
         let mut e: HashSet<_> = all_chars;
 
-        for c in candidates.keys() {
+        for (c, mapped) in &candidates {
             if c != &'e' {
-                let mapped_c = candidates[c].iter().nth(0).unwrap();
-                e.remove(mapped_c);
+                for mapped_c in mapped {
+                    e.remove(mapped_c);
+                }
             }
         }
 
         candidates.insert('e', e);
     }
 
-    assert!(candidates.values().all(|c| c.len() == 1));
+    if candidates.values().any(|c| c.len() != 1) {
+        return Err(Error::no_solution(format!(
+            "Failed to find a unique wiring for {:?}",
+            segments
+        )));
+    }
 
     let mapped_digits: HashMap<String, usize> = all_digits
         .values()
@@ -185,7 +207,7 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
             let mut mapped_segments: Vec<_> = d
                 .segments
                 .iter()
-                .map(|c| candidates[c].iter().nth(0).unwrap())
+                .map(|c| candidates[c].iter().next().unwrap())
                 .copied()
                 .collect();
             mapped_segments.sort();
@@ -194,104 +216,87 @@ fn solve_line(segments: Vec<HashSet<char>>, outputs: &[String]) -> usize {
         })
         .collect();
 
-    let num_digits = outputs.len();
-    outputs.into_iter().enumerate().fold(0, |acc, (idx, s)| {
-        acc + mapped_digits[s.as_str()] * 10_usize.pow((num_digits - idx - 1) as u32)
-    })
-}
-
-pub fn star_one(input: &str) -> usize {
-    let clean_lines = input.lines().map(str::trim).filter(|l| l.len() > 0);
-    let parsed = clean_lines.map(|l| {
-        let mut parts = l.split("|").map(str::trim);
-
-        match (parts.next(), parts.next()) {
-            (Some(patterns), Some(outputs)) => (
-                patterns
-                    .split_whitespace()
-                    .map(|s| sorted_chars(s.chars()).into_iter().collect::<HashSet<_>>())
-                    .collect(),
-                outputs
-                    .split_whitespace()
-                    .map(|s| sorted_chars(s.chars()))
-                    .map(|s| s.into_iter().collect())
-                    .collect::<Vec<String>>(),
-            ),
-            _ => unreachable!("Each line must have two parts, not true for {}", l),
-        }
-    });
-
-    parsed
-        .map(|(patterns, outputs)| {
-            let mut number = solve_line(patterns, &outputs);
-            let mut exponent = ((number as f64).log10().floor()) as i32;
-            let mut count = 0;
+    let digits = outputs
+        .iter()
+        .map(|s| {
+            mapped_digits
+                .get(s.as_str())
+                .copied()
+                .ok_or_else(|| Error::invalid_input(format!("Unknown output digit {}", s)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-            while exponent >= 0 {
-                let n = number / 10_usize.pow(exponent as u32);
+    DigitIterator::from_digits(digits, 10)
+        .ok_or_else(|| Error::invalid_input(format!("Output {:?} is too large", outputs)))
+}
 
-                if n == 7 || n == 4 || n == 1 || n == 8 {
-                    count += 1;
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec<(Vec<HashSet<char>>, Vec<String>)>;
+    type One = usize;
+    type Two = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let clean_lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        clean_lines
+            .map(|l| {
+                let mut parts = l.split('|').map(str::trim);
+
+                match (parts.next(), parts.next()) {
+                    (Some(patterns), Some(outputs)) => Ok((
+                        patterns
+                            .split_whitespace()
+                            .map(|s| sorted_chars(s.chars()).into_iter().collect::<HashSet<_>>())
+                            .collect(),
+                        outputs
+                            .split_whitespace()
+                            .map(|s| sorted_chars(s.chars()))
+                            .map(|s| s.into_iter().collect())
+                            .collect::<Vec<String>>(),
+                    )),
+                    _ => Err(Error::invalid_input(format!(
+                        "Each line must have two parts, not true for {}",
+                        l
+                    ))),
                 }
+            })
+            .collect()
+    }
 
-                number = number % 10_usize.pow(exponent as u32);
-                exponent -= 1;
-            }
+    fn star_one(entries: &Self::Input) -> Result<Self::One, Error> {
+        entries
+            .iter()
+            .map(|(patterns, outputs)| {
+                let number = solve_line(patterns.clone(), outputs)?;
+
+                Ok(DigitIterator::new(number)
+                    .filter(|d| matches!(d, 1 | 4 | 7 | 8))
+                    .count())
+            })
+            .sum()
+    }
 
-            count
-        })
-        .sum()
+    fn star_two(entries: &Self::Input) -> Result<Self::Two, Error> {
+        entries
+            .iter()
+            .map(|(patterns, outputs)| solve_line(patterns.clone(), outputs))
+            .sum()
+    }
 }
 
-pub fn star_two(input: &str) -> usize {
-    let clean_lines = input.lines().map(str::trim).filter(|l| l.len() > 0);
-    let parsed = clean_lines.map(|l| {
-        let mut parts = l.split("|").map(str::trim);
-
-        match (parts.next(), parts.next()) {
-            (Some(patterns), Some(outputs)) => (
-                patterns
-                    .split_whitespace()
-                    .map(|s| sorted_chars(s.chars()).into_iter().collect::<HashSet<_>>())
-                    .collect(),
-                outputs
-                    .split_whitespace()
-                    .map(|s| sorted_chars(s.chars()))
-                    .map(|s| s.into_iter().collect())
-                    .collect::<Vec<String>>(),
-            ),
-            _ => unreachable!("Each line must have two parts, not true for {}", l),
-        }
-    });
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day08::star_one(&Day08::parse(input)?)
+}
 
-    parsed
-        .map(|(patterns, outputs)| solve_line(patterns, &outputs))
-        .sum()
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day08::star_two(&Day08::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_line, star_one, star_two};
-    const INPUT: &str = r#"be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
-edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
-fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
-fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
-aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
-fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
-dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
-bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
-egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
-gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 26);
-    }
-
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 61229);
-    }
+    use super::solve_line;
 
     #[test]
     fn test_solve_line() {
@@ -303,14 +308,14 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
         assert_eq!(
             solve_line(
                 segments,
-                &vec![
+                &[
                     "bcdef".into(),
                     "abcdf".into(),
                     "bcdef".into(),
                     "abcdf".into()
                 ]
             ),
-            5353
+            Ok(5353)
         );
     }
 
@@ -324,14 +329,14 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
         assert_eq!(
             solve_line(
                 segments,
-                &vec![
+                &[
                     "abcdefg".into(),
                     "bcdef".into(),
                     "bcdefg".into(),
                     "bceg".into()
                 ]
             ),
-            8394
+            Ok(8394)
         );
     }
 }
@@ -1,131 +1,78 @@
-use std::{collections::HashSet, rc::Rc};
+use std::{cmp::Reverse, collections::HashSet};
 
-fn neighbors(p: (usize, usize), max: (usize, usize)) -> Vec<(usize, usize)> {
-    let mut result = Vec::with_capacity(4);
+use crate::{
+    math::{Grid, Neighborhood, Vector2},
+    Error, Solution,
+};
 
-    if p.0 > 0 {
-        result.push((p.0 - 1, p.1));
-    }
-
-    if p.1 > 0 {
-        result.push((p.0, p.1 - 1));
-    }
+fn find_low_points(grid: &Grid<u8>) -> Vec<(Vector2<usize>, u32)> {
+    grid.iter()
+        .filter_map(|(location, p)| {
+            let is_lowpoint = grid
+                .neighbors(location, Neighborhood::Four)
+                .all(|n| grid[n] > *p);
 
-    if p.0 < (max.0 - 1) {
-        result.push((p.0 + 1, p.1));
-    }
+            is_lowpoint.then(|| (location, *p as u32 + 1))
+        })
+        .collect()
+}
 
-    if p.1 < (max.1 - 1) {
-        result.push((p.0, p.1 + 1));
-    }
+fn find_basin(grid: &Grid<u8>, low_point: Vector2<usize>) -> HashSet<Vector2<usize>> {
+    let mut result = HashSet::with_capacity(4);
+    let mut to_visit = vec![low_point];
+    result.insert(low_point);
 
-    result
-}
+    while let Some(p) = to_visit.pop() {
+        for n in grid.neighbors(p, Neighborhood::Four) {
+            let nv = grid[n];
 
-fn neighbors_recurse<F>(
-    p: (usize, usize),
-    max: (usize, usize),
-    should_recurse: Rc<F>,
-    result: &mut HashSet<(usize, usize)>,
-) where
-    F: Fn(&(usize, usize), &(usize, usize)) -> bool,
-{
-    let direct_neighbors = neighbors(p, max);
-
-    for n in direct_neighbors {
-        if should_recurse(&n, &p) && !result.contains(&n) {
-            result.insert(n.clone());
-
-            neighbors_recurse(n, max, should_recurse.clone(), result);
+            if nv > grid[p] && nv != 9 && result.insert(n) {
+                to_visit.push(n);
+            }
         }
     }
+
+    result
 }
 
-fn find_low_points(grid: &[Vec<u32>]) -> Vec<((usize, usize), u32)> {
-    let grid_size = (grid[0].len(), grid.len());
+pub struct Day09;
 
-    let grid_ref = &grid;
+impl Solution for Day09 {
+    type Input = Grid<u8>;
+    type One = u32;
+    type Two = usize;
 
-    grid.iter()
-        .enumerate()
-        .flat_map(move |(y, row)| {
-            row.iter().enumerate().filter_map(move |(x, p)| {
-                let is_lowpoint = neighbors((x, y), grid_size)
-                    .into_iter()
-                    .all(|o| grid_ref[o.1][o.0] > *p);
-
-                is_lowpoint.then(|| ((x, y), p + 1))
-            })
-        })
-        .collect()
-}
-
-pub fn star_one(input: &str) -> u32 {
-    let grid: Vec<Vec<_>> = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 1)
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Grid::parse_digits(input)
+    }
 
-    find_low_points(&grid).into_iter().map(|(_, p)| p).sum()
-}
+    fn star_one(grid: &Self::Input) -> Result<Self::One, Error> {
+        Ok(find_low_points(grid).into_iter().map(|(_, p)| p).sum())
+    }
 
-pub fn star_two(input: &str) -> usize {
-    let grid: Vec<Vec<_>> = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 1)
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect();
-
-    let low_points = find_low_points(&grid);
-
-    let grid_size = (grid[0].len(), grid.len());
-    let grid_ref = &grid;
-
-    let mut basins: Vec<HashSet<(usize, usize)>> = low_points
-        .into_iter()
-        .map(move |((x, y), _)| {
-            let mut result = HashSet::with_capacity(4);
-            result.insert((x, y));
-
-            neighbors_recurse(
-                (x, y),
-                grid_size,
-                Rc::new(|np: &(usize, usize), p: &(usize, usize)| {
-                    let nv = grid_ref[np.1][np.0];
-
-                    nv > grid_ref[p.1][p.0] && nv != 9
-                }),
-                &mut result,
-            );
-
-            result
-        })
-        .collect();
+    fn star_two(grid: &Self::Input) -> Result<Self::Two, Error> {
+        let mut basins: Vec<HashSet<Vector2<usize>>> = find_low_points(grid)
+            .into_iter()
+            .map(|(low_point, _)| find_basin(grid, low_point))
+            .collect();
+
+        if basins.len() < 3 {
+            return Err(Error::no_solution(format!(
+                "Expected at least three basins, found {}",
+                basins.len()
+            )));
+        }
 
-    basins.sort_by(|a, b| b.len().cmp(&a.len()));
+        basins.sort_by_key(|b| Reverse(b.len()));
 
-    basins.into_iter().take(3).map(|b| b.len()).product()
+        Ok(basins.into_iter().take(3).map(|b| b.len()).product())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"2199943210
-3987894921
-9856789892
-8767896789
-9899965678"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 15);
-    }
+pub fn star_one(input: &str) -> Result<u32, Error> {
+    Day09::star_one(&Day09::parse(input)?)
+}
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 1134);
-    }
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day09::star_two(&Day09::parse(input)?)
 }
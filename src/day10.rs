@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 
+use crate::{Error, Solution};
+
 trait CharExt {
     fn is_opening(&self) -> bool;
     fn is_closing(&self) -> bool;
@@ -57,79 +59,83 @@ impl CharExt for char {
 fn find_first_illegal(line: &str) -> Result<char, VecDeque<char>> {
     let mut stack = VecDeque::<char>::new();
 
+    // Lines are validated to only contain opening and closing characters when parsing
     for c in line.chars() {
-        assert!(c.is_opening() || c.is_closing());
-
         if c.is_opening() {
             stack.push_front(c);
         } else {
-            let popped = stack.pop_front().unwrap();
-
-            if popped.closing_for_open() != c {
-                return Ok(c);
+            match stack.pop_front() {
+                Some(popped) if popped.closing_for_open() == c => {}
+                // Either the wrong closing character or one without a matching opening character
+                _ => return Ok(c),
             }
         }
     }
 
     Err(stack)
 }
-pub fn star_one(input: &str) -> u64 {
-    let result = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .filter_map(|l| find_first_illegal(l).ok());
-
-    result.map(|c| c.score_corrupted()).sum()
-}
 
-pub fn star_two(input: &str) -> u64 {
-    let scores: Vec<_> = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .filter_map(|l| find_first_illegal(l).err())
-        .map(|stack| {
-            stack.into_iter().fold(0, |acc, c| {
-                let closing = c.closing_for_open();
-                let score = closing.score_uncorrupted();
-
-                (acc * 5) + score
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| match l.chars().find(|c| !c.is_opening() && !c.is_closing()) {
+                Some(c) => Err(Error::invalid_input(format!(
+                    "Unexpected character `{}` in `{}`",
+                    c, l
+                ))),
+                None => Ok(String::from(l)),
             })
-        })
-        .collect();
+            .collect()
+    }
 
-    let sorted_scores = {
-        let mut scores = scores;
-        scores.sort();
+    fn star_one(lines: &Self::Input) -> Result<Self::One, Error> {
+        let result = lines.iter().filter_map(|l| find_first_illegal(l).ok());
 
-        scores
-    };
+        Ok(result.map(|c| c.score_corrupted()).sum())
+    }
 
-    sorted_scores[sorted_scores.len() / 2]
-}
+    fn star_two(lines: &Self::Input) -> Result<Self::Two, Error> {
+        let scores: Vec<_> = lines
+            .iter()
+            .filter_map(|l| find_first_illegal(l).err())
+            .map(|stack| {
+                stack.into_iter().fold(0, |acc, c| {
+                    let closing = c.closing_for_open();
+                    let score = closing.score_uncorrupted();
+
+                    (acc * 5) + score
+                })
+            })
+            .collect();
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"[({(<(())[]>[[{[]{<()<>>
-[(()[<>])]({[<{<<[]>>(
-{([(<{}[<>[]}>{[]{[(<()>
-(((({<>}<{<{<>}{[]{[]{}
-[[<[([]))<([[{}[[()]]]
-[{[{({}]{}}([{[{{{}}([]
-{<[[]]>}<{[{[{[]{()[[[]
-[<(<(<(<{}))><([]([]()
-<{([([[(<>()){}]>(<<{{
-<{([{{}}[<[[[<>{}]]]>[]]"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 26397);
-    }
+        let sorted_scores = {
+            let mut scores = scores;
+            scores.sort();
+
+            scores
+        };
+
+        if sorted_scores.is_empty() {
+            return Err(Error::no_solution("There are no incomplete lines"));
+        }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 288957);
+        Ok(sorted_scores[sorted_scores.len() / 2])
     }
 }
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day10::star_one(&Day10::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day10::star_two(&Day10::parse(input)?)
+}
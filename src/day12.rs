@@ -1,10 +1,12 @@
 use std::{
-    collections::{hash_map::Entry, HashMap, HashSet, LinkedList, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet},
     hash::Hash,
     rc::Rc,
     str::FromStr,
 };
 
+use crate::{search, Error, Solution};
+
 type ID = String;
 
 #[derive(Debug, Default)]
@@ -79,7 +81,7 @@ impl Cave {
 }
 
 #[derive(Debug)]
-struct System {
+pub struct System {
     start: ID,
     end: ID,
     caves: HashMap<ID, Cave>,
@@ -87,19 +89,21 @@ struct System {
 
 impl System {
     fn find_cave(&self, id: &ID) -> &Cave {
-        self.caves.get(id).unwrap()
+        self.caves
+            .get(id)
+            .expect("Every connection is added as a cave when parsing")
     }
 }
 
 impl FromStr for System {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut start = None;
         let mut end = None;
         let mut caves = HashMap::default();
 
-        let clean_lines = s.lines().map(str::trim).filter(|l| l.len() > 0);
+        let clean_lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
 
         fn add_cave(caves: &mut HashMap<ID, Cave>, id: &str, other_id: &str) {
             let cave = caves
@@ -110,7 +114,7 @@ impl FromStr for System {
         }
 
         for line in clean_lines {
-            let mut parts = line.split("-");
+            let mut parts = line.split('-');
 
             match (parts.next().map(str::trim), parts.next().map(str::trim)) {
                 (Some(c1_id), Some(c2_id)) => {
@@ -134,201 +138,127 @@ impl FromStr for System {
                     }
                 }
                 _ => {
-                    return Err(format!("Invalid cave system:\n{}", s));
+                    return Err(Error::invalid_input(format!("Invalid connection {}", line)));
                 }
             };
         }
 
-        Ok(System {
-            start: start.unwrap(),
-            end: end.unwrap(),
-            caves,
-        })
+        match (start, end) {
+            (Some(start), Some(end)) => Ok(System { start, end, caves }),
+            (None, _) => Err(Error::invalid_input("Missing `start` cave")),
+            (_, None) => Err(Error::invalid_input("Missing `end` cave")),
+        }
     }
 }
 
-#[derive(Default, Debug)]
-struct State {
-    path: LinkedList<ID>,
+#[derive(Debug, Clone)]
+struct State<'a> {
+    cave: &'a Cave,
     visited_small_caves: HashCow<ID, usize>,
 }
 
-impl State {
-    fn new(initial: ID) -> Self {
-        let mut list = LinkedList::new();
-        list.push_front(initial);
+impl<'a> State<'a> {
+    fn new(cave: &'a Cave) -> Self {
+        let mut visited_small_caves = HashCow::default();
+        if cave.is_small {
+            visited_small_caves.insert(cave.id.clone(), 1);
+        }
 
         Self {
-            path: list,
-            visited_small_caves: Default::default(),
+            cave,
+            visited_small_caves,
         }
     }
-}
 
-fn explore<F>(system: &System, from: ID, mut should_continue: F) -> Vec<Vec<ID>>
-where
-    F: FnMut(&Cave, &State) -> bool,
-{
-    let mut stack = VecDeque::<State>::new();
-    {
-        let mut initial_state = State::new(from.clone());
-        if from.chars().all(char::is_lowercase) {
-            initial_state.visited_small_caves.insert(from, 1);
+    fn visit(&self, next: &'a Cave) -> Self {
+        let mut visited_small_caves = self.visited_small_caves.clone();
+        if next.is_small {
+            *visited_small_caves.entry(next.id.clone()).or_default() += 1;
         }
-        stack.push_back(initial_state);
-    }
-
-    let mut end_states: Vec<State> = vec![];
-
-    while let Some(state) = stack.pop_back() {
-        let current = state.path.back().unwrap();
-        let cave = system.find_cave(current);
-
-        for next_id in cave.connections.iter() {
-            let next_cave = system.find_cave(next_id);
-
-            if !should_continue(&next_cave, &state) {
-                continue;
-            }
 
-            let path = {
-                let mut p = state.path.clone();
-                p.push_back(next_id.clone());
-
-                p
-            };
-            let visited_small_caves = {
-                let mut s = state.visited_small_caves.clone();
-
-                if next_cave.is_small {
-                    *s.entry(next_id.into()).or_default() += 1;
-                }
-
-                s
-            };
-            let new_state = State {
-                path,
-                visited_small_caves,
-            };
-
-            if next_id == &system.end {
-                end_states.push(new_state);
-            } else {
-                stack.push_back(new_state);
-            }
+        Self {
+            cave: next,
+            visited_small_caves,
         }
     }
-
-    end_states
-        .into_iter()
-        .map(|s| s.path.into_iter().collect())
-        .collect()
 }
 
-pub fn star_one(input: &str) -> usize {
-    let system = System::from_str(input).expect("Failed to parse cave system");
-
-    let paths = explore(&system, system.start.clone(), |cave, state| {
-        state
-            .visited_small_caves
-            .get(&cave.id)
-            .map(|c| c == &0)
-            .unwrap_or(true)
-    });
-
-    paths.len()
+fn count_paths<F>(system: &System, should_continue: F) -> usize
+where
+    F: Fn(&Cave, &State) -> bool,
+{
+    search::count_paths(
+        State::new(system.find_cave(&system.start)),
+        |state| {
+            state
+                .cave
+                .connections
+                .iter()
+                .map(|id| system.find_cave(id))
+                .filter(|next| should_continue(next, state))
+                .map(|next| state.visit(next))
+                .collect::<Vec<_>>()
+        },
+        |state| state.cave.id == system.end,
+    )
 }
 
-pub fn star_two(input: &str) -> usize {
-    let system = System::from_str(input).expect("Failed to parse cave system");
+pub struct Day12;
 
-    let paths = explore(&system, system.start.clone(), |cave, state| {
-        // if cave.id == system.start && state.path.len() == 1 {
-        //     return true;
-        // }
+impl Solution for Day12 {
+    type Input = System;
+    type One = usize;
+    type Two = usize;
 
-        if cave.id == system.start {
-            return false;
-        }
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        System::from_str(input)
+    }
 
-        let visited_twice =
+    fn star_one(system: &Self::Input) -> Result<Self::One, Error> {
+        let count = count_paths(system, |cave, state| {
             state
                 .visited_small_caves
-                .iter()
-                .find_map(|(id, c)| if c >= &2 { Some(id) } else { None });
+                .get(&cave.id)
+                .map(|c| c == &0)
+                .unwrap_or(true)
+        });
 
-        state
-            .visited_small_caves
-            .get(&cave.id)
-            .map(|c| (visited_twice.is_none() && c < &2) || (visited_twice.is_some() && c == &0))
-            .unwrap_or(true)
-    });
+        Ok(count)
+    }
 
-    paths.len()
-}
+    fn star_two(system: &Self::Input) -> Result<Self::Two, Error> {
+        let count = count_paths(system, |cave, state| {
+            // if cave.id == system.start && state.path.len() == 1 {
+            //     return true;
+            // }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end"#;
-
-    const INPUT_MEDIUM: &str = r#"dc-end
-HN-start
-start-kj
-dc-start
-dc-HN
-LN-dc
-HN-end
-kj-sa
-kj-HN
-kj-dc"#;
-
-    const INPUT_LARGE: &str = r#"fs-end
-he-DX
-fs-he
-start-DX
-pj-DX
-end-zg
-zg-sl
-zg-pj
-pj-he
-RW-he
-fs-DX
-pj-RW
-zg-RW
-start-pj
-he-WI
-zg-he
-pj-fs
-start-RW"#;
-
-    #[test]
-    fn test_star_one() {
-        for (input, result) in &[(INPUT, 10), (INPUT_MEDIUM, 19), (INPUT_LARGE, 226)] {
-            assert_eq!(
-                star_one(input),
-                *result,
-                "Wrong result returned for: \n{}",
-                input
-            );
-        }
-    }
+            if cave.id == system.start {
+                return false;
+            }
 
-    #[test]
-    fn test_star_two() {
-        for (input, result) in &[(INPUT, 36), (INPUT_MEDIUM, 103), (INPUT_LARGE, 3509)] {
-            assert_eq!(
-                star_two(input),
-                *result,
-                "Wrong result returned for: \n{}",
-                input
-            );
-        }
+            let visited_twice =
+                state
+                    .visited_small_caves
+                    .iter()
+                    .find_map(|(id, c)| if c >= &2 { Some(id) } else { None });
+
+            state
+                .visited_small_caves
+                .get(&cave.id)
+                .map(|c| {
+                    (visited_twice.is_none() && c < &2) || (visited_twice.is_some() && c == &0)
+                })
+                .unwrap_or(true)
+        });
+
+        Ok(count)
     }
 }
+
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day12::star_one(&Day12::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day12::star_two(&Day12::parse(input)?)
+}
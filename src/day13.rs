@@ -1,26 +1,29 @@
 use std::{collections::HashSet, str::FromStr};
 
-use crate::parse_custom_separated;
+use crate::{math::Grid, ocr, try_parse_custom_separated, Error, Solution};
 
 #[derive(PartialEq, Eq)]
-enum Axis {
+pub enum Axis {
     Horizontal,
     Vertical,
 }
 
 impl FromStr for Axis {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "x" => Ok(Self::Horizontal),
             "y" => Ok(Self::Vertical),
-            _ => Err(format!("Invalid axis: {}", s)),
+            _ => Err(Error::invalid_input(format!("Invalid axis: {}", s))),
         }
     }
 }
 
-fn location_around_fold(loc: (u64, u64), fold: &(Axis, u64)) -> (u64, u64) {
+type Dot = (u64, u64);
+type Fold = (Axis, u64);
+
+fn location_around_fold(loc: Dot, fold: &Fold) -> Dot {
     let (axis, fold_location) = fold;
 
     if axis == &Axis::Horizontal {
@@ -41,109 +44,109 @@ fn location_around_fold(loc: (u64, u64), fold: &(Axis, u64)) -> (u64, u64) {
     }
 }
 
-fn parse_input(
-    input: &str,
-) -> (
-    impl Iterator<Item = (u64, u64)> + '_,
-    impl Iterator<Item = (Axis, u64)> + '_,
-) {
-    let mut parts = input.trim().split("\n\n");
-    let grid = parts
-        .next()
-        .unwrap()
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .map(|l| {
-            let mut iter = parse_custom_separated::<u64>(l, ",");
-
-            (iter.next().unwrap(), iter.next().unwrap())
-        });
-
-    let folds = parts
-        .next()
-        .unwrap()
-        .lines()
-        .map(str::trim)
-        .filter(|l| l.len() > 0)
-        .map(|s| {
-            let rest = s.strip_prefix("fold along ").unwrap();
-            let mut parts = rest.split("=");
-
-            match (parts.next().map(str::trim), parts.next().map(str::trim)) {
-                (Some(axis), Some(value)) => {
-                    let parsed_axis: Axis = axis
-                        .parse()
-                        .expect(&format!("Failed to parse axis in: {}", s));
-
-                    (
-                        parsed_axis,
-                        value
-                            .parse::<u64>()
-                            .expect(&format!("Failed to parse fold location in: {}", s)),
-                    )
-                }
-                _ => unreachable!(),
-            }
-        });
-
-    (grid, folds)
+fn parse_dot(line: &str) -> Result<Dot, Error> {
+    let mut iter = try_parse_custom_separated::<u64>(line, ",");
+
+    match (iter.next(), iter.next(), iter.next()) {
+        (Some(x), Some(y), None) => Ok((x?, y?)),
+        _ => Err(Error::invalid_input(format!("Invalid dot {}", line))),
+    }
 }
 
-fn print_grid(grid: HashSet<(u64, u64)>) {
-    let max = grid
-        .iter()
-        .fold((0, 0), |acc, &(x, y)| (acc.0.max(x), acc.1.max(y)));
-    dbg!(max);
-
-    let rows = (0..=max.1)
-        .map(|y| {
-            (0..=max.0)
-                .map(|x| {
-                    if grid.contains(&(x, y)) {
-                        "\u{2588}"
-                    } else {
-                        " "
-                    }
-                })
-                .collect::<String>()
-        })
-        .collect::<Vec<_>>();
-
-    println!("{}", rows.join("\n"));
+fn parse_fold(line: &str) -> Result<Fold, Error> {
+    let invalid_fold = || Error::invalid_input(format!("Invalid fold {}", line));
+
+    let rest = line.strip_prefix("fold along ").ok_or_else(invalid_fold)?;
+    let (axis, value) = rest.split_once('=').ok_or_else(invalid_fold)?;
+
+    let axis: Axis = axis.trim().parse()?;
+    let value = value.trim().parse::<u64>().map_err(|_| invalid_fold())?;
+
+    if value == 0 {
+        return Err(invalid_fold());
+    }
+
+    Ok((axis, value))
 }
 
-pub fn star_one(input: &str) -> usize {
-    let (grid, mut folds) = parse_input(input);
+fn parse_input(input: &str) -> Result<(Vec<Dot>, Vec<Fold>), Error> {
+    let (dots, folds) = input
+        .trim()
+        .split_once("\n\n")
+        .ok_or_else(|| Error::invalid_input("Expected dots and folds separated by a blank line"))?;
+
+    fn clean_lines(s: &str) -> impl Iterator<Item = &str> {
+        s.lines().map(str::trim).filter(|l| !l.is_empty())
+    }
 
-    let first_fold = folds.nth(0).unwrap();
+    Ok((
+        clean_lines(dots).map(parse_dot).collect::<Result<_, _>>()?,
+        clean_lines(folds)
+            .map(parse_fold)
+            .collect::<Result<_, _>>()?,
+    ))
+}
 
-    let locations: HashSet<_> = grid
-        .map(|loc| location_around_fold(loc, &first_fold))
-        .collect();
+/// The dots as pixels, cropped to the lit ones.
+fn to_pixels(dots: &HashSet<Dot>) -> Grid<bool> {
+    let (width, height) = dots
+        .iter()
+        .fold((0, 0), |acc, &(x, y)| (acc.0.max(x + 1), acc.1.max(y + 1)));
 
-    locations.len()
+    Grid::from_fn(width as usize, height as usize, |p| {
+        dots.contains(&(p.x as u64, p.y as u64))
+    })
 }
 
-pub fn star_two(input: &str) -> &str {
-    let (grid, folds) = parse_input(input);
-    let mut grid: HashSet<_> = grid.collect();
+pub struct Day13;
 
-    for fold in folds {
-        grid = grid
-            .into_iter()
-            .map(|loc| location_around_fold(loc, &fold))
+impl Solution for Day13 {
+    type Input = (Vec<Dot>, Vec<Fold>);
+    type One = usize;
+    type Two = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
+
+    fn star_one((grid, folds): &Self::Input) -> Result<Self::One, Error> {
+        let first_fold = folds
+            .first()
+            .ok_or_else(|| Error::invalid_input("Expected at least one fold"))?;
+
+        let locations: HashSet<_> = grid
+            .iter()
+            .map(|&loc| location_around_fold(loc, first_fold))
             .collect();
+
+        Ok(locations.len())
+    }
+
+    fn star_two((grid, folds): &Self::Input) -> Result<Self::Two, Error> {
+        let mut grid: HashSet<_> = grid.iter().copied().collect();
+
+        for fold in folds {
+            grid = grid
+                .into_iter()
+                .map(|loc| location_around_fold(loc, fold))
+                .collect();
+        }
+
+        ocr::recognize(&to_pixels(&grid))
     }
+}
 
-    print_grid(grid);
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day13::star_one(&Day13::parse(input)?)
+}
 
-    "CPJBERVL"
+pub fn star_two(input: &str) -> Result<String, Error> {
+    Day13::star_two(&Day13::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::star_one;
+    use super::star_two;
     const INPUT: &str = r#"6,10
 0,14
 9,10
@@ -167,7 +170,8 @@ fold along y=7
 fold along x=5"#;
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 17);
+    fn test_star_two() {
+        // Folding the example leaves a square, which isn't a letter
+        assert!(star_two(INPUT).is_err());
     }
 }
@@ -1,5 +1,9 @@
 use std::{collections::HashMap, mem};
 
+use crate::{Error, Solution};
+
+type Rules = HashMap<(char, char), char>;
+
 fn estimate_size(template_length: usize, steps: usize) -> usize {
     let mut n = template_length;
 
@@ -13,7 +17,7 @@ fn estimate_size(template_length: usize, steps: usize) -> usize {
 // This is how I solved part 1, which clearly doens't work for part 2.
 fn expand(
     input: &str,
-    pairs: &HashMap<(char, char), char>,
+    pairs: &Rules,
     steps: usize,
 ) -> impl Iterator<Item = char> {
     let size = estimate_size(input.len(), steps);
@@ -43,7 +47,7 @@ fn expand(
 
 fn expand_smort(
     input: &str,
-    pairs: &HashMap<(char, char), char>,
+    pairs: &Rules,
     steps: usize,
 ) -> impl Iterator<Item = ((char, char), usize)> {
     let template: Vec<_> = input.chars().collect();
@@ -69,30 +73,61 @@ fn expand_smort(
     result.into_iter()
 }
 
-fn parse(input: &str) -> (&str, HashMap<(char, char), char>) {
-    let mut parts = input.split("\n\n").map(str::trim);
-    let template = parts.next().unwrap();
-    let pairs: HashMap<_, _> = parts
-        .next()
-        .unwrap()
+fn parse(input: &str) -> Result<(&str, Rules), Error> {
+    let (template, rules) = input
+        .trim()
+        .split_once("\n\n")
+        .ok_or_else(|| Error::invalid_input("Expected template and rules separated by a blank line"))?;
+    let template = template.trim();
+
+    if template.is_empty() {
+        return Err(Error::invalid_input("Empty template"));
+    }
+
+    let pairs: HashMap<_, _> = rules
         .lines()
         .map(str::trim)
-        .filter(|l| l.len() > 0)
+        .filter(|l| !l.is_empty())
         .map(|l| {
-            let mut parts = l.split("->").map(str::trim);
-
-            let pattern = parts.next().unwrap();
-            let (p1, p2) = (
-                pattern.chars().next().unwrap(),
-                pattern.chars().skip(1).next().unwrap(),
-            );
-            let substitution = parts.next().unwrap();
-
-            ((p1, p2), substitution.chars().next().unwrap())
+            let invalid_rule = || Error::invalid_input(format!("Invalid rule {}", l));
+            let (pattern, substitution) = l.split_once("->").ok_or_else(invalid_rule)?;
+
+            let mut pattern = pattern.trim().chars();
+            let mut substitution = substitution.trim().chars();
+
+            match (
+                pattern.next(),
+                pattern.next(),
+                pattern.next(),
+                substitution.next(),
+                substitution.next(),
+            ) {
+                (Some(p1), Some(p2), None, Some(s), None) => Ok(((p1, p2), s)),
+                _ => Err(invalid_rule()),
+            }
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
+
+    let template_chars: Vec<_> = template.chars().collect();
+    let required_pairs = template_chars
+        .windows(2)
+        .map(|chars| (chars[0], chars[1]))
+        .chain(
+            pairs
+                .iter()
+                .flat_map(|(&(p1, p2), &s)| [(p1, s), (s, p2)]),
+        );
+
+    for (p1, p2) in required_pairs {
+        if !pairs.contains_key(&(p1, p2)) {
+            return Err(Error::invalid_input(format!(
+                "Missing rule for pair {}{}",
+                p1, p2
+            )));
+        }
+    }
 
-    (template, pairs)
+    Ok((template, pairs))
 }
 
 fn count(
@@ -108,66 +143,55 @@ fn count(
             });
 
     // Whather the last character is it will have been under counted by one, fix it.
-    *counts.get_mut(&last).unwrap() += 1;
+    *counts.entry(last).or_default() += 1;
 
     counts
 }
 
-pub fn star_one(input: &str) -> usize {
-    let (template, pairs) = parse(input);
-    let result = expand_smort(template, &pairs, 10);
-    let last = template.chars().last().unwrap();
+fn most_minus_least_common(
+    (template, pairs): &(String, Rules),
+    steps: usize,
+) -> usize {
+    let result = expand_smort(template, pairs, steps);
+    let last = template
+        .chars()
+        .last()
+        .expect("Templates are never empty after parsing");
 
     let counts = count(result, last);
 
-    let max = counts.values().max_by_key(|&count| count).unwrap();
-    let min = counts.values().min_by_key(|&count| count).unwrap();
+    let max = counts.values().max().expect("There's at least one count");
+    let min = counts.values().min().expect("There's at least one count");
 
     max - min
 }
 
-pub fn star_two(input: &str) -> usize {
-    let (template, pairs) = parse(input);
-    let result = expand_smort(template, &pairs, 40);
-    let last = template.chars().last().unwrap();
+pub struct Day14;
 
-    let counts = count(result, last);
+impl Solution for Day14 {
+    type Input = (String, Rules);
+    type One = usize;
+    type Two = usize;
 
-    let max = counts.values().max_by_key(|&count| count).unwrap();
-    let min = counts.values().min_by_key(|&count| count).unwrap();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let (template, pairs) = parse(input)?;
 
-    max - min
-}
+        Ok((template.to_owned(), pairs))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"NNCB
-
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C"#;
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 1588);
+    fn star_one(input: &Self::Input) -> Result<Self::One, Error> {
+        Ok(most_minus_least_common(input, 10))
     }
 
-    #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 2188189693529);
+    fn star_two(input: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(most_minus_least_common(input, 40))
     }
 }
+
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day14::star_one(&Day14::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day14::star_two(&Day14::parse(input)?)
+}
@@ -1,212 +1,136 @@
-use std::collections::{BinaryHeap, HashMap};
-use std::ops::Index;
-
-type Grid = Vec<Vec<u8>>;
-
-#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq)]
-struct Point<T> {
-    x: T,
-    y: T,
+use crate::{
+    math::{self, Grid, Neighborhood, Vector2},
+    search, Error, Solution,
+};
+
+/// Risk levels wrap around from 9 back to 1 as they increase, `risk` must be at least 1.
+fn wrap_risk(risk: u8, increase: usize) -> u8 {
+    ((risk as usize - 1 + increase) % 9 + 1) as u8
 }
 
-impl<T> Point<T> {
-    const ORIGIN: Point<usize> = Point { x: 0, y: 0 };
-
-    fn new(x: T, y: T) -> Self {
-        Self { x, y }
-    }
+/// A view of a base grid repeated `tiles` times in both directions, without storing the repeats.
+///
+/// The value of each cell is the base cell's value passed through `rule` together with how many
+/// tiles right and down the cell's tile is.
+#[derive(Clone, Copy)]
+pub struct TiledGrid<'a> {
+    base: &'a Grid<u8>,
+    tiles: usize,
+    rule: fn(u8, usize) -> u8,
 }
 
-fn neighbors(p: Point<usize>, max: Point<usize>) -> Vec<Point<usize>> {
-    let mut result = Vec::with_capacity(4);
-
-    if p.x > 0 {
-        result.push(Point::new(p.x - 1, p.y));
+impl<'a> TiledGrid<'a> {
+    /// Tile `base` with the puzzle's rule of risk increasing by one per tile.
+    pub fn new(base: &'a Grid<u8>, tiles: usize) -> Self {
+        Self::with_rule(base, tiles, wrap_risk)
     }
 
-    if p.y > 0 {
-        result.push(Point::new(p.x, p.y - 1));
+    pub fn with_rule(base: &'a Grid<u8>, tiles: usize, rule: fn(u8, usize) -> u8) -> Self {
+        Self { base, tiles, rule }
     }
 
-    if p.x < (max.x - 1) {
-        result.push(Point::new(p.x + 1, p.y));
-    }
+    pub fn size(&self) -> Vector2<usize> {
+        let size = self.base.size();
 
-    if p.y < (max.y - 1) {
-        result.push(Point::new(p.x, p.y + 1));
+        Vector2::new(size.x * self.tiles, size.y * self.tiles)
     }
 
-    result
-}
+    pub fn get(&self, location: Vector2<usize>) -> Option<u8> {
+        let base_size = self.base.size();
+        if base_size.x == 0 || base_size.y == 0 {
+            return None;
+        }
 
-#[derive(Debug)]
-struct HeapEntry<T> {
-    location: Point<usize>,
-    value: T,
-}
+        let tile = Vector2::new(location.x / base_size.x, location.y / base_size.y);
 
-impl<T: Ord> HeapEntry<T> {
-    fn new(location: Point<usize>, value: T) -> Self {
-        Self { location, value }
-    }
-}
+        if tile.x >= self.tiles || tile.y >= self.tiles {
+            return None;
+        }
 
-impl<T: Ord> Ord for HeapEntry<T> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.value.cmp(&self.value)
-    }
-}
-impl<T: Ord> PartialOrd for HeapEntry<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
+        let base = self.base[Vector2::new(location.x % base_size.x, location.y % base_size.y)];
 
-impl<T: PartialEq> PartialEq for HeapEntry<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.location.eq(&other.location) && self.value.eq(&other.value)
+        Some((self.rule)(base, tile.x + tile.y))
     }
-}
-impl<T: Eq> Eq for HeapEntry<T> {}
 
-impl<T> Index<Point<usize>> for Vec<Vec<T>> {
-    type Output = T;
+    pub fn neighbors(
+        &self,
+        location: Vector2<usize>,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = Vector2<usize>> {
+        let size = self.size();
 
-    fn index(&self, index: Point<usize>) -> &Self::Output {
-        &self[index.y][index.x]
+        math::neighbors_within(location, (size.x, size.y), neighborhood)
     }
 }
 
-impl<T> Index<&Point<usize>> for Vec<Vec<T>> {
-    type Output = T;
-
-    fn index(&self, index: &Point<usize>) -> &Self::Output {
-        &self[index.y][index.x]
-    }
+fn lowest_total_risk(grid: &TiledGrid) -> Result<usize, Error> {
+    let size = grid.size();
+    if size.x == 0 || size.y == 0 {
+        return Err(Error::invalid_input("The cave is empty"));
+    }
+    let goal = Vector2::new(size.x - 1, size.y - 1);
+
+    // Every step costs at least one, so the distance left never overestimates the risk left
+    let path = search::astar(
+        Vector2::default(),
+        |&p| {
+            grid.neighbors(p, Neighborhood::Four)
+                .filter_map(|n| Some(((), n, grid.get(n)? as usize)))
+        },
+        |p| (goal.x - p.x) + (goal.y - p.y),
+        |p| *p == goal,
+    )
+    .ok_or_else(|| Error::no_solution("No path to the bottom right"))?;
+
+    Ok(path.cost)
 }
 
-fn reconstruct_path(
-    came_from: HashMap<Point<usize>, Point<usize>>,
-    from: Point<usize>,
-) -> Vec<Point<usize>> {
-    let mut path = vec![from];
-    let mut current = &from;
+pub struct Day15;
 
-    while let Some(next) = came_from.get(current) {
-        path.push(*next);
-        current = next;
-    }
-
-    path.reverse();
-    path
-}
+impl Solution for Day15 {
+    type Input = Grid<u8>;
+    type One = usize;
+    type Two = usize;
 
-fn djikstra(grid: &Grid, from: Point<usize>, to: Point<usize>) -> Option<Vec<Point<usize>>> {
-    let max = Point::new(grid[0].len(), grid.len());
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let grid = Grid::parse_digits(input)?;
 
-    let mut open = BinaryHeap::new();
-    for x in 0..max.x {
-        for y in 0..max.y {
-            let p = Point::new(x, y);
+        let zero = grid.iter().find(|(_, &risk)| risk == 0).map(|(p, _)| p);
 
-            if p != from {
-                open.push(HeapEntry::new(Point::new(x, y), usize::MAX));
-            }
+        match zero {
+            Some(location) => Err(Error::invalid_input(format!(
+                "Risk levels are 1 to 9, found 0 at {},{}",
+                location.x, location.y
+            ))),
+            None => Ok(grid),
         }
     }
-    open.push(HeapEntry::new(from, grid[from].into()));
 
-    let mut came_from: HashMap<Point<usize>, Point<usize>> = Default::default();
-
-    let mut distance: HashMap<Point<usize>, usize> = Default::default();
-    distance.insert(from, grid[from].into());
-
-    while !open.is_empty() {
-        let current = open.pop().unwrap();
-
-        if current.location == to {
-            return Some(reconstruct_path(came_from, to));
-        }
-
-        for neighbor in neighbors(current.location, max) {
-            let alt = distance
-                .get(&current.location)
-                .map(|d| d + grid[neighbor] as usize)
-                .unwrap_or(usize::MAX);
-
-            if alt < *distance.get(&neighbor).unwrap_or(&usize::MAX) {
-                distance.insert(neighbor, alt);
-                came_from.insert(neighbor, current.location);
-                open.push(HeapEntry::new(neighbor, alt));
-            }
-        }
+    fn star_one(grid: &Self::Input) -> Result<Self::One, Error> {
+        lowest_total_risk(&TiledGrid::new(grid, 1))
     }
 
-    None
+    fn star_two(grid: &Self::Input) -> Result<Self::Two, Error> {
+        lowest_total_risk(&TiledGrid::new(grid, 5))
+    }
 }
 
-pub fn star_one(input: &str) -> usize {
-    let grid: Grid = input
-        .lines()
-        .map(str::trim)
-        .filter(|l| !l.is_empty())
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as u8).collect())
-        .collect();
-
-    let goal = Point::new(grid[0].len() - 1, grid.len() - 1);
-    let path = djikstra(&grid, Point::<usize>::ORIGIN, goal).unwrap();
-
-    path.into_iter().skip(1).map(|p| grid[p] as usize).sum()
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day15::star_one(&Day15::parse(input)?)
 }
 
-pub fn star_two(input: &str) -> usize {
-    let grid: Grid = {
-        let og_grid: Grid = input
-            .lines()
-            .map(str::trim)
-            .filter(|l| !l.is_empty())
-            .map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as u8).collect())
-            .collect();
-        let og_size = Point::new(og_grid[0].len(), og_grid.len());
-        let grid_size = Point::new(og_size.x * 5, og_size.y * 5);
-
-        let mut grid: Grid = vec![];
-        for _ in 0..grid_size.y {
-            let row = Vec::with_capacity(grid_size.x * 5);
-            grid.push(row);
-        }
-
-        for y in 0..grid_size.y {
-            for x in 0..grid_size.x {
-                if x < og_size.x && y < og_size.y {
-                    grid[y].push(og_grid[y][x]);
-                } else {
-                    let x0 = if x >= og_size.x && y < og_size.y {
-                        x - og_size.x
-                    } else {
-                        x
-                    };
-                    let y0 = if y >= og_size.y { y - og_size.y } else { y };
-                    let value = ((grid[y0][x0] + 1) % 10).max(1);
-
-                    grid[y].push(value);
-                }
-            }
-        }
-
-        grid
-    };
-
-    let goal = Point::new(grid[0].len() - 1, grid.len() - 1);
-    let path = djikstra(&grid, Point::<usize>::ORIGIN, goal).unwrap();
-
-    path.into_iter().skip(1).map(|p| grid[p] as usize).sum()
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day15::star_two(&Day15::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
-    const INPUT: &str = r#"1163751742
+    use super::{lowest_total_risk, Day15, TiledGrid};
+    use crate::math::{Grid, Vector2};
+    use crate::Solution;
+
+    const EXAMPLE: &str = "\
+1163751742
 1381373672
 2136511328
 3694931569
@@ -215,15 +139,56 @@ mod tests {
 1359912421
 3125421639
 1293138521
-2311944581"#;
+2311944581";
+
+    fn row(grid: &TiledGrid, y: usize) -> String {
+        (0..grid.size().x)
+            .map(|x| char::from(b'0' + grid.get(Vector2::new(x, y)).unwrap()))
+            .collect()
+    }
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(INPUT), 40);
+    fn test_tiled_grid() {
+        let base = Grid::parse_digits(EXAMPLE).unwrap();
+        let tiled = TiledGrid::new(&base, 5);
+
+        assert_eq!(tiled.size(), Vector2::new(50, 50));
+        assert_eq!(
+            row(&tiled, 0),
+            "11637517422274862853338597396444961841755517295286"
+        );
+        assert_eq!(
+            row(&tiled, 49),
+            "67554889357866599146897761125791887223681299833479"
+        );
+        assert_eq!(tiled.get(Vector2::new(50, 0)), None);
+        assert_eq!(tiled.get(Vector2::new(0, 50)), None);
+
+        let large = TiledGrid::new(&base, 50);
+        assert_eq!(large.size(), Vector2::new(500, 500));
+        assert_eq!(large.get(Vector2::new(499, 499)), Some(9));
+
+        let flat = TiledGrid::with_rule(&base, 3, |risk, _| risk);
+        assert_eq!(
+            flat.get(Vector2::new(21, 13)),
+            base.get(Vector2::new(1, 3)).copied()
+        );
+        assert_eq!(lowest_total_risk(&TiledGrid::new(&base, 1)), Ok(40));
+    }
+
+    #[test]
+    fn test_empty_tiled_grid() {
+        let empty = Grid::from_fn(0, 0, |_| 1);
+        let tiled = TiledGrid::new(&empty, 5);
+
+        assert_eq!(tiled.size(), Vector2::new(0, 0));
+        assert_eq!(tiled.get(Vector2::new(0, 0)), None);
+        assert!(lowest_total_risk(&tiled).is_err());
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(INPUT), 315);
+    fn test_zero_risk() {
+        assert!(Day15::parse("1163\n1091").is_err());
+        assert!(Day15::parse("1163\n1191").is_ok());
     }
 }
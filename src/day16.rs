@@ -1,19 +1,37 @@
 use std::str::FromStr;
 
-pub fn star_one(input: &str) -> u64 {
-    let packet = input.trim().parse::<Packet>().unwrap();
+use crate::{Error, Solution};
 
-    packet.version_sum()
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Packet;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input.trim().parse::<Packet>()
+    }
+
+    fn star_one(packet: &Self::Input) -> Result<Self::One, Error> {
+        Ok(packet.version_sum())
+    }
+
+    fn star_two(packet: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(packet.resolve())
+    }
 }
 
-pub fn star_two(input: &str) -> u64 {
-    let packet = input.trim().parse::<Packet>().unwrap();
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day16::star_one(&Day16::parse(input)?)
+}
 
-    packet.resolve()
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day16::star_two(&Day16::parse(input)?)
 }
 
 #[derive(Debug, Eq, PartialEq)]
-enum Packet {
+pub enum Packet {
     Literal {
         type_id: TypeId,
         version: u8,
@@ -27,6 +45,33 @@ enum Packet {
 }
 
 impl Packet {
+    /// Check that an operator packet has a valid number of sub packets for its type.
+    fn validate(&self) -> Result<(), Error> {
+        let (type_id, count) = match self {
+            Packet::Literal { .. } => return Ok(()),
+            Packet::Operator {
+                type_id,
+                sub_packets,
+                ..
+            } => (type_id, sub_packets.len()),
+        };
+
+        let is_valid = match type_id {
+            TypeId::Sum | TypeId::Product | TypeId::Min | TypeId::Max => count >= 1,
+            TypeId::Gt | TypeId::Lt | TypeId::Eq => count == 2,
+            TypeId::Literal => unreachable!("Operator packets never have the literal type"),
+        };
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(Error::invalid_input(format!(
+                "Operator packet of type {:?} can't have {} sub packets",
+                type_id, count
+            )))
+        }
+    }
+
     fn version_sum(&self) -> u64 {
         match self {
             Packet::Literal { version, .. } => *version as u64,
@@ -38,6 +83,7 @@ impl Packet {
         }
     }
 
+    /// Evaluate the expression, the number of sub packets is validated when parsing.
     fn resolve(&self) -> u64 {
         match self {
             Packet::Literal { value, .. } => *value,
@@ -88,18 +134,18 @@ impl Packet {
 }
 
 impl FromStr for Packet {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bytes = to_bytes(s);
-        let (p, _) = parse_packet(0, &bytes);
+        let bytes = to_bytes(s)?;
+        let (p, _) = parse_packet(0, &bytes)?;
 
-        return Ok(p);
+        Ok(p)
     }
 }
 
 #[derive(Debug, Eq, PartialEq)]
-enum TypeId {
+pub enum TypeId {
     Sum = 0,
     Product = 1,
     Min = 2,
@@ -121,7 +167,7 @@ impl TypeId {
 }
 
 impl TryFrom<u8> for TypeId {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
@@ -133,7 +179,7 @@ impl TryFrom<u8> for TypeId {
             x if x == Self::Gt as u8 => Ok(Self::Gt),
             x if x == Self::Lt as u8 => Ok(Self::Lt),
             x if x == Self::Eq as u8 => Ok(Self::Eq),
-            _ => Err(format!("Invalid TypeId {}", value)),
+            _ => Err(Error::invalid_input(format!("Invalid TypeId {}", value))),
         }
     }
 }
@@ -156,19 +202,42 @@ fn mask_out_bits(count: u8, at: u8, from: u8) -> u8 {
     (mask & from) >> shift
 }
 
-fn to_bytes(input: &str) -> Vec<u8> {
+fn to_bytes(input: &str) -> Result<Vec<u8>, Error> {
     let chars: Vec<_> = input.chars().collect();
 
+    if chars.len() % 2 != 0 {
+        return Err(Error::invalid_input(format!(
+            "Expected an even number of hex digits in {}",
+            input
+        )));
+    }
+
     chars
         .chunks(2)
-        .map(|c| {
-            let (h, l) = (c[0], c[1]);
-
-            ((h.to_digit(16).unwrap() << 4) | (l.to_digit(16).unwrap())) as u8
+        .map(|c| match (c[0].to_digit(16), c[1].to_digit(16)) {
+            (Some(h), Some(l)) => Ok(((h << 4) | l) as u8),
+            _ => Err(Error::invalid_input(format!(
+                "Invalid hex byte {}{}",
+                c[0], c[1]
+            ))),
         })
         .collect()
 }
 
+/// Check that there are at least `count` bits left at `at` in `from`.
+fn ensure_bits(count: usize, at: usize, from: &[u8]) -> Result<(), Error> {
+    if at + count > from.len() * 8 {
+        Err(Error::invalid_input(format!(
+            "Expected {} more bits at bit {}, but the transmission is only {} bits",
+            count,
+            at,
+            from.len() * 8
+        )))
+    } else {
+        Ok(())
+    }
+}
+
 fn debug_print(at: usize, from: &[u8]) {
     let byte = at / 8;
     let bit_in_byte = at % 8;
@@ -188,12 +257,13 @@ fn debug_print(at: usize, from: &[u8]) {
     }
 }
 
-fn parse_packet(at: usize, from: &[u8]) -> (Packet, usize) {
-    let (version, type_id) = extract_type_and_version(at, &from);
-    let type_id: TypeId = type_id.try_into().unwrap();
+fn parse_packet(at: usize, from: &[u8]) -> Result<(Packet, usize), Error> {
+    ensure_bits(6, at, from)?;
+    let (version, type_id) = extract_type_and_version(at, from);
+    let type_id: TypeId = type_id.try_into()?;
 
     if type_id.is_literal() {
-        let (value, new_index) = parse_literal(at, &from);
+        let (value, new_index) = parse_literal(at, from)?;
 
         let p = Packet::Literal {
             type_id,
@@ -201,16 +271,16 @@ fn parse_packet(at: usize, from: &[u8]) -> (Packet, usize) {
             value,
         };
 
-        (p, new_index)
+        Ok((p, new_index))
     } else {
-        let (length_type_id, length, new_index) = parse_operator(at, &from);
+        let (length_type_id, length, new_index) = parse_operator(at, from)?;
 
         let (sub_packets, new_index) = if length_type_id == 0 {
             let mut result = vec![];
             let mut current_index = new_index;
 
             while (current_index + 6) < (new_index + length as usize) {
-                let (p, op_index) = parse_packet(current_index, &from);
+                let (p, op_index) = parse_packet(current_index, from)?;
                 result.push(p);
                 current_index = op_index;
             }
@@ -221,7 +291,7 @@ fn parse_packet(at: usize, from: &[u8]) -> (Packet, usize) {
             let mut current_index = new_index;
 
             for _ in 0..length {
-                let (p, op_index) = parse_packet(current_index, &from);
+                let (p, op_index) = parse_packet(current_index, from)?;
                 result.push(p);
                 current_index = op_index;
             }
@@ -234,18 +304,21 @@ fn parse_packet(at: usize, from: &[u8]) -> (Packet, usize) {
             version,
             sub_packets,
         };
+        packet.validate()?;
 
-        (packet, new_index)
+        Ok((packet, new_index))
     }
 }
 
-fn parse_literal(at: usize, from: &[u8]) -> (u64, usize) {
-    let (_, t) = extract_type_and_version(at, &from);
+fn parse_literal(at: usize, from: &[u8]) -> Result<(u64, usize), Error> {
+    ensure_bits(6, at, from)?;
+    let (_, t) = extract_type_and_version(at, from);
     assert!(t == 4, "Literal packets should have type 4");
 
     let mut bit_index: usize = at + 6;
     let mut nibbles = vec![];
     loop {
+        ensure_bits(5, bit_index, from)?;
         let first_bit = extract_bits(1, bit_index, from);
         let should_continue = first_bit == 1;
 
@@ -259,52 +332,62 @@ fn parse_literal(at: usize, from: &[u8]) -> (u64, usize) {
     }
 
     let nibble_count = nibbles.len();
+    if nibble_count > 16 {
+        return Err(Error::invalid_input(format!(
+            "Literal at bit {} doesn't fit in 64 bits",
+            at
+        )));
+    }
+
     let value = nibbles
         .into_iter()
         .enumerate()
         .fold(0_u64, |acc, (n_idx, v)| {
-            acc | (v << (nibble_count - n_idx - 1) * 4)
+            acc | (v << ((nibble_count - n_idx - 1) * 4))
         });
 
-    (value, bit_index)
+    Ok((value, bit_index))
 }
 
-fn parse_operator(at: usize, from: &[u8]) -> (u8, u32, usize) {
-    let (_, t) = extract_type_and_version(at, &from);
+fn parse_operator(at: usize, from: &[u8]) -> Result<(u8, u32, usize), Error> {
+    ensure_bits(7, at, from)?;
+    let (_, t) = extract_type_and_version(at, from);
     assert!(t != 4, "Operator packets should NOT have type 4");
 
     let bit_index: usize = at + 6;
-    let length_type_id = extract_bits(1, bit_index, &from) as u8;
+    let length_type_id = extract_bits(1, bit_index, from) as u8;
 
     if length_type_id == 0 {
         // Next 15 bits contain the length of the sub packets
+        ensure_bits(15, bit_index + 1, from)?;
 
         // extract_bits can't handle counts larger than 8
         let b1 = extract_bits(8, bit_index + 1, from);
         let b2 = extract_bits(7, bit_index + 9, from);
 
-        (
+        Ok((
             length_type_id,
-            ((b1 << 7) | b2).try_into().unwrap(),
+            ((b1 << 7) | b2) as u32,
             bit_index + 16,
-        )
+        ))
     } else {
         // Next 11 bits contain the number of sub packets.
+        ensure_bits(11, bit_index + 1, from)?;
 
         // extract_bits can't handle counts larger than 8
         let b1 = extract_bits(8, bit_index + 1, from);
         let b2 = extract_bits(3, bit_index + 9, from);
 
-        (
+        Ok((
             length_type_id,
-            ((b1 << 3) | b2).try_into().unwrap(),
+            ((b1 << 3) | b2) as u32,
             bit_index + 12,
-        )
+        ))
     }
 }
 
 fn extract_bits(count: u8, at: usize, from: &[u8]) -> u64 {
-    let start_byte_index: usize = (at / 8).into();
+    let start_byte_index: usize = at / 8;
 
     // The index into the first byte
     let bit_index: u8 = (at % 8) as u8;
@@ -330,57 +413,16 @@ fn extract_bits(count: u8, at: usize, from: &[u8]) -> u64 {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::{
         extract_bits, extract_type_and_version, mask_out_bits, parse_literal, parse_operator,
-        star_one, star_two, to_bytes, Packet,
+        to_bytes, Packet,
     };
 
-    #[test]
-    fn test_star_one() {
-        let cases = [
-            ("8A004A801A8002F478", 16),
-            ("620080001611562C8802118E34", 12),
-            ("C0015000016115A2E0802F182340", 23),
-            ("A0016C880162017C3686B18A3D4780", 31),
-        ];
-
-        for (input, expected) in cases {
-            assert_eq!(
-                star_one(input),
-                expected,
-                "Expected correct answer for input `{}`",
-                input
-            );
-        }
-    }
-
-    #[test]
-    fn test_star_two() {
-        let cases = [
-            ("C200B40A82", 3),
-            ("04005AC33890", 54),
-            ("880086C3E88112", 7),
-            ("CE00C43D881120", 9),
-            ("D8005AC2A8F0", 1),
-            ("F600BC2D8F", 0),
-            ("9C005AC2F8F0", 0),
-            ("9C0141080250320F1802104A08", 1),
-        ];
-
-        for (input, expected) in cases {
-            assert_eq!(
-                star_two(input),
-                expected,
-                "Expected correct answer for input `{}`",
-                input
-            );
-        }
-    }
-
     #[test]
     fn test_to_bytes() {
-        let result = to_bytes("D2FE");
+        let result = to_bytes("D2FE").unwrap();
 
         assert_eq!(result, vec![0xD2, 0xFE]);
     }
@@ -426,6 +468,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::type_complexity)]
     fn test_extract_bits() {
         let cases: &[((&[u8], u8, usize), u64)] = &[
             ((&[0b00101101], 6, 2), 0b101101),
@@ -448,9 +491,9 @@ mod tests {
 
     #[test]
     fn test_parse_literal() {
-        let bytes = to_bytes("D2FE28");
+        let bytes = to_bytes("D2FE28").unwrap();
 
-        let (literal, bit_index) = parse_literal(0, &bytes);
+        let (literal, bit_index) = parse_literal(0, &bytes).unwrap();
 
         assert_eq!(literal, 2021);
         assert_eq!(bit_index, 21);
@@ -458,9 +501,9 @@ mod tests {
 
     #[test]
     fn test_parse_operator_zero_type_length_id() {
-        let bytes = to_bytes("38006F45291200");
+        let bytes = to_bytes("38006F45291200").unwrap();
 
-        let (type_length_id, length, bit_index) = parse_operator(0, &bytes);
+        let (type_length_id, length, bit_index) = parse_operator(0, &bytes).unwrap();
 
         assert_eq!(type_length_id, 0);
         assert_eq!(length, 27);
@@ -469,9 +512,9 @@ mod tests {
 
     #[test]
     fn test_parse_operator_one_type_length_id() {
-        let bytes = to_bytes("EE00D40C823060");
+        let bytes = to_bytes("EE00D40C823060").unwrap();
 
-        let (type_length_id, length, bit_index) = parse_operator(0, &bytes);
+        let (type_length_id, length, bit_index) = parse_operator(0, &bytes).unwrap();
 
         assert_eq!(type_length_id, 1);
         assert_eq!(length, 3);
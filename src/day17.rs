@@ -1,177 +1,358 @@
-use std::collections::HashSet;
-
-#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq)]
-struct Point<T> {
-    x: T,
-    y: T,
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::{math::Vector2, Error, Solution};
+
+/// The target area, inclusive of `min` and `max`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Area {
+    min: Vector2<i64>,
+    max: Vector2<i64>,
 }
 
-impl<T> Point<T> {
-    const ORIGIN: Point<usize> = Point { x: 0, y: 0 };
+impl Area {
+    pub fn new(min: Vector2<i64>, max: Vector2<i64>) -> Option<Self> {
+        (min.x <= max.x && min.y <= max.y).then_some(Self { min, max })
+    }
+
+    pub fn min(&self) -> Vector2<i64> {
+        self.min
+    }
 
-    fn new(x: T, y: T) -> Self {
-        Self { x, y }
+    pub fn max(&self) -> Vector2<i64> {
+        self.max
+    }
+
+    fn contains(&self, p: &Vector2<i64>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
     }
 }
 
-impl<T: Default + PartialEq + Eq> Point<T> {
-    fn is_origin(&self) -> bool {
-        self.x == T::default() && self.y == T::default()
+impl FromStr for Area {
+    type Err = Error;
+
+    /// Parse a target area, e.g. `target area: x=20..30, y=-10..-5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_area = || Error::invalid_input(format!("Invalid target area {}", s.trim()));
+
+        let rest = s
+            .trim()
+            .strip_prefix("target area:")
+            .ok_or_else(invalid_area)?;
+        let mut ranges = rest.split(',').zip(["x=", "y="]).map(|(range, axis)| {
+            let (start, end) = range.trim().strip_prefix(axis)?.split_once("..")?;
+
+            Some((
+                start.trim().parse::<i64>().ok()?,
+                end.trim().parse::<i64>().ok()?,
+            ))
+        });
+
+        match (ranges.next(), ranges.next(), rest.split(',').count()) {
+            (Some(Some(x)), Some(Some(y)), 2) => {
+                Area::new(Vector2::new(x.0, y.0), Vector2::new(x.1, y.1)).ok_or_else(invalid_area)
+            }
+            _ => Err(invalid_area()),
+        }
     }
 }
 
-struct Area {
-    min: Point<i64>,
-    max: Point<i64>,
+type Path = Vec<Vector2<i64>>;
+
+fn calculate_new_velocity(current: Vector2<i64>) -> Vector2<i64> {
+    // Drag pulls x towards zero while gravity always pulls y down
+    current - Vector2::new(current.x.signum(), 1)
 }
 
-impl Area {
-    fn contains(&self, p: &Point<i64>) -> bool {
-        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
-    }
+/// The flight of a probe launched from the origin, up to the step it enters the target area or
+/// until it can no longer reach it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    velocity: Vector2<i64>,
+    area: Area,
+    path: Path,
+    entry: Option<usize>,
 }
 
-type Path = Vec<Point<i64>>;
+impl Trajectory {
+    pub fn launch(velocity: Vector2<i64>, area: &Area) -> Self {
+        let mut path = vec![];
+        let mut entry = None;
 
-/// Calculate the number of steps needed to be simulated to know if the target area has been
-/// reached.
-///
-/// Assumes that velocity.y is positive
-fn calculate_steps_required(y_velocity: i64, target_area: &Area) -> usize {
-    // Number of steps to get back to origin
-    let steps_to_origin = (y_velocity * 2 + 1) as usize;
+        let mut position = Vector2::new(0, 0);
+        let mut current = velocity;
+
+        loop {
+            position += current;
+            path.push(position);
+            current = calculate_new_velocity(current);
 
-    let mut velocity = -y_velocity;
-    let mut y_pos = 0;
-    let mut extra_steps = 0;
+            if area.contains(&position) {
+                entry = Some(path.len());
+                break;
+            }
 
-    while y_pos > target_area.min.y {
-        y_pos += velocity;
-        velocity -= 1;
+            // Falling below the target area, or past or beside it horizontally with drag never
+            // turning the probe around, means it will never hit
+            let below = current.y < 0 && position.y < area.min.y;
+            let right = current.x >= 0 && position.x > area.max.x;
+            let left = current.x <= 0 && position.x < area.min.x;
+            if below || right || left {
+                break;
+            }
+        }
+
+        Self {
+            velocity,
+            area: *area,
+            path,
+            entry,
+        }
+    }
 
-        extra_steps += 1;
+    pub fn velocity(&self) -> Vector2<i64> {
+        self.velocity
     }
 
-    steps_to_origin + extra_steps
+    /// The positions after each step, excluding the launch position.
+    pub fn path(&self) -> &[Vector2<i64>] {
+        &self.path
+    }
+
+    pub fn is_hit(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    /// The step, counting from one, after which the probe is first inside the target area.
+    pub fn entry_step(&self) -> Option<usize> {
+        self.entry
+    }
+
+    /// The highest position reached, the launch position if the probe never goes up.
+    pub fn apex(&self) -> Vector2<i64> {
+        self.path.iter().fold(
+            Vector2::new(0, 0),
+            |apex, &p| if p.y > apex.y { p } else { apex },
+        )
+    }
 }
 
-fn calculate_new_velocity(current: Point<i64>) -> Point<i64> {
-    let new_x_velocity = current.x + current.x.signum() * -1;
+/// Draws the trajectory like the puzzle's illustrations, with `S` for the launch position, `#`
+/// for the probe after each step and `T` for the target area.
+impl fmt::Display for Trajectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points = self.path.iter().chain([&self.area.min, &self.area.max]);
+        let (min, max) = points.fold((Vector2::new(0, 0), Vector2::new(0, 0)), |(min, max), p| {
+            (
+                Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        });
+        let path: HashSet<_> = self.path.iter().copied().collect();
+
+        for y in (min.y..=max.y).rev() {
+            if y != max.y {
+                writeln!(f)?;
+            }
+
+            for x in min.x..=max.x {
+                let p = Vector2::new(x, y);
+                let c = if x == 0 && y == 0 {
+                    'S'
+                } else if path.contains(&p) {
+                    '#'
+                } else if self.area.contains(&p) {
+                    'T'
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+        }
 
-    Point::new(new_x_velocity, current.y - 1)
+        Ok(())
+    }
+}
+
+/// The sum `1 + 2 + ... + n`, how far drag or gravity lets a velocity of `n` travel.
+fn triangle(n: i64) -> i64 {
+    n * (n + 1) / 2
 }
 
-fn simulate(
-    from: Point<i64>,
-    initial_velocity: Point<i64>,
-    steps: usize,
-    area: &Area,
-) -> Option<(Point<i64>, Path)> {
-    if initial_velocity.x > area.max.x && initial_velocity.y > area.max.y {
-        // There's no way
-        return None;
+/// The smallest `n >= 0` with `triangle(n) >= value`.
+fn triangle_root_ceil(value: i64) -> i64 {
+    if value <= 0 {
+        return 0;
     }
 
-    let mut path = Vec::with_capacity(steps);
+    // The float estimate is only a starting point, it's corrected in both directions
+    let mut n = (2.0 * value as f64).sqrt() as i64;
+    while triangle(n) < value {
+        n += 1;
+    }
+    while n > 0 && triangle(n - 1) >= value {
+        n -= 1;
+    }
 
-    let mut position = from;
-    let mut velocity = initial_velocity;
+    n
+}
 
-    for _ in 0..steps {
-        position = Point::new(position.x + velocity.x, position.y + velocity.y);
-        path.push(position);
+/// The largest `n >= 0` with `triangle(n) <= value`, `-1` when `value` is negative.
+fn triangle_root_floor(value: i64) -> i64 {
+    if value < 0 {
+        return -1;
+    }
 
-        velocity = calculate_new_velocity(velocity);
+    triangle_root_ceil(value + 1) - 1
+}
 
-        if area.contains(&position) {
-            return Some((initial_velocity, path));
-        }
+fn div_floor(a: i64, b: i64) -> i64 {
+    a.div_euclid(b)
+}
 
-        if velocity.y < 0 && position.y < area.min.y {
-            // We are falling below the target area and will thus never hit it
-            return None;
-        } else if velocity.x == 0 && (position.x < area.min.x || position.x > area.max.x) {
-            // We are falling straight down to the right or left of the target area straight down
-            return None;
-        }
-    }
+fn div_ceil(a: i64, b: i64) -> i64 {
+    -(-a).div_euclid(b)
+}
+
+/// The x velocities that put the probe inside the area's x range after exactly `step` steps.
+///
+/// A velocity of at least `step` in either direction is still moving and has travelled
+/// `vx * step ∓ triangle(step - 1)`, anything slower has stopped at `±triangle(|vx|)`.
+fn x_velocities_at(area: &Area, step: i64) -> [RangeInclusive<i64>; 4] {
+    let drag = triangle(step - 1);
+
+    [
+        // Still moving right
+        div_ceil(area.min.x + drag, step).max(step)..=div_floor(area.max.x + drag, step),
+        // Still moving left
+        div_ceil(area.min.x - drag, step)..=div_floor(area.max.x - drag, step).min(-step),
+        // Stopped to the right
+        triangle_root_ceil(area.min.x)..=triangle_root_floor(area.max.x).min(step - 1),
+        // Stopped to the left
+        -triangle_root_floor(-area.min.x).min(step - 1)..=-triangle_root_ceil(-area.max.x),
+    ]
+}
 
-    None
+/// The y velocities that put the probe inside the area's y range after exactly `step` steps,
+/// having travelled `vy * step - triangle(step - 1)`.
+fn y_velocities_at(area: &Area, step: i64) -> RangeInclusive<i64> {
+    let gravity = triangle(step - 1);
+
+    div_ceil(area.min.y + gravity, step)..=div_floor(area.max.y + gravity, step)
+}
+
+/// The largest number of steps after which a probe can still be inside the area.
+///
+/// For an area below the launch position a probe launched upwards with `vy` comes back to `y = 0`
+/// after `2 * vy + 1` steps moving at `-(vy + 1)`, so it only hits if `vy < -min.y`. That probe
+/// leaves the area after `-2 * min.y` steps, and any slower one is lower at every step. For an
+/// area above, every height the probe reaches is at least `vy`, so `vy <= max.y` and it has fallen
+/// back below the area after `2 * max.y` steps.
+fn max_steps(area: &Area) -> Result<i64, Error> {
+    if area.max.y < 0 {
+        Ok(-2 * area.min.y)
+    } else if area.min.y > 0 {
+        Ok(2 * area.max.y)
+    } else {
+        Err(Error::invalid_input(
+            "Target areas level with the launch position have unbounded trajectories",
+        ))
+    }
 }
 
-pub fn star_one(min: (i64, i64), max: (i64, i64)) -> i64 {
-    let area = Area {
-        min: Point::new(min.0, min.1),
-        max: Point::new(max.0, max.1),
-    };
-    let area_ref = &area;
-    let mut results = vec![];
+/// Every initial velocity that hits the area, with the first step at which it's inside it.
+fn hits(area: &Area) -> Result<HashMap<Vector2<i64>, i64>, Error> {
+    let mut hits = HashMap::new();
 
-    for y in min.1..1000 {
-        let steps = calculate_steps_required(y, &area);
-        for x in 0..=area.max.x {
-            if let Some(path) = simulate(Point::new(0, 0), Point::new(x, y), steps, area_ref) {
-                results.push(path);
+    for step in 1..=max_steps(area)? {
+        let ys = y_velocities_at(area, step);
+        if ys.is_empty() {
+            continue;
+        }
+
+        for xs in x_velocities_at(area, step) {
+            for x in xs {
+                for y in ys.clone() {
+                    hits.entry(Vector2::new(x, y)).or_insert(step);
+                }
             }
         }
     }
 
-    let max_y = results
-        .into_iter()
-        .map(|(_, path)| {
-            let highest_point = path.iter().max_by_key(|p| p.y).copied().unwrap();
+    Ok(hits)
+}
 
-            highest_point.y
-        })
-        .max()
-        .unwrap();
+/// The height of the [`Trajectory::apex`] of a probe launched at `vy` during its first `steps`
+/// steps, the launch height of zero if it never goes up.
+fn highest_point(vy: i64, steps: i64) -> i64 {
+    let step = steps.min(vy).max(0);
 
-    max_y
+    vy * step - triangle(step - 1)
 }
 
-pub fn star_two(min: (i64, i64), max: (i64, i64)) -> usize {
-    let area = Area {
-        min: Point::new(min.0, min.1),
-        max: Point::new(max.0, max.1),
-    };
+pub struct Day17;
 
-    let mut unique_velocities: HashSet<Point<i64>> = Default::default();
+impl Solution for Day17 {
+    type Input = Area;
+    type One = i64;
+    type Two = usize;
 
-    for y in min.1..1000 {
-        let steps = calculate_steps_required(y, &area);
-        for x in 0..=area.max.x {
-            let velocity = Point::new(x, y);
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input.parse()
+    }
 
-            if let Some(_) = simulate(Point::new(0, 0), velocity, steps, &area) {
-                unique_velocities.insert(velocity);
-            }
-        }
+    fn star_one(area: &Self::Input) -> Result<Self::One, Error> {
+        hits(area)?
+            .into_iter()
+            .map(|(velocity, step)| highest_point(velocity.y, step))
+            .max()
+            .ok_or_else(|| Error::no_solution("No trajectory hits the target area"))
+    }
+
+    fn star_two(area: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(hits(area)?.len())
     }
-    unique_velocities.len()
+}
+
+pub fn star_one(input: &str) -> Result<i64, Error> {
+    Day17::star_one(&Day17::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day17::star_two(&Day17::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{calculate_new_velocity, simulate, star_one, star_two, Area, Point};
-
-    #[test]
-    fn test_star_one() {
-        assert_eq!(star_one((20, -10), (30, -5)), 45);
-    }
+    use super::{calculate_new_velocity, highest_point, hits, Area, Day17, Trajectory, Vector2};
+    use crate::Solution;
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two((20, -10), (30, -5)), 112);
+    fn test_parse_area() {
+        let area: Area = "target area: x=-30..-20, y=-10..-5".parse().unwrap();
+
+        assert_eq!(area.min(), Vector2::new(-30, -10));
+        assert_eq!(area.max(), Vector2::new(-20, -5));
+
+        assert!("target area: x=30..20, y=-10..-5".parse::<Area>().is_err());
+        assert!("target area: y=-10..-5, x=20..30".parse::<Area>().is_err());
+        assert!("target area: x=20..30".parse::<Area>().is_err());
+        assert!("target area: x=20..30, y=-10..-5, z=1..2"
+            .parse::<Area>()
+            .is_err());
+        assert!("x=20..30, y=-10..-5".parse::<Area>().is_err());
     }
 
     #[test]
     fn test_calculate_new_velocity() {
-        let cases: &[(Point<i64>, Point<i64>)] = &[
-            (Point::<i64>::new(0, 0), Point::<i64>::new(0, -1)),
-            (Point::<i64>::new(1, 0), Point::<i64>::new(0, -1)),
-            (Point::<i64>::new(-1, 0), Point::<i64>::new(0, -1)),
-            (Point::<i64>::new(-5, 0), Point::<i64>::new(-4, -1)),
-            (Point::<i64>::new(5, 0), Point::<i64>::new(4, -1)),
+        let cases: &[(Vector2<i64>, Vector2<i64>)] = &[
+            (Vector2::new(0, 0), Vector2::new(0, -1)),
+            (Vector2::new(1, 0), Vector2::new(0, -1)),
+            (Vector2::new(-1, 0), Vector2::new(0, -1)),
+            (Vector2::new(-5, 0), Vector2::new(-4, -1)),
+            (Vector2::new(5, 0), Vector2::new(4, -1)),
         ];
 
         for (velocity, expected_velocity) in cases {
@@ -180,14 +361,127 @@ mod tests {
     }
 
     #[test]
-    fn test_simulate() {
-        let area = Area {
-            min: Point::new(20, -10),
-            max: Point::new(30, -5),
-        };
+    fn test_launch() {
+        let area: Area = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let mirrored: Area = "target area: x=-30..-20, y=-10..-5".parse().unwrap();
+
+        let hit = Trajectory::launch(Vector2::new(7, 2), &area);
+        assert!(hit.is_hit());
+        assert_eq!(hit.entry_step(), Some(7));
+        assert_eq!(hit.apex(), Vector2::new(13, 3));
+        assert_eq!(hit.path().last(), Some(&Vector2::new(28, -7)));
+
+        let mirrored_hit = Trajectory::launch(Vector2::new(-9, 0), &mirrored);
+        assert_eq!(mirrored_hit.entry_step(), Some(4));
+        assert_eq!(mirrored_hit.apex(), Vector2::new(0, 0));
+
+        let overshoot = Trajectory::launch(Vector2::new(31, -1), &area);
+        assert!(!overshoot.is_hit());
+        assert_eq!(overshoot.path(), &[Vector2::new(31, -1)]);
+
+        let through = Trajectory::launch(Vector2::new(17, -4), &area);
+        assert!(!through.is_hit());
+        assert_eq!(through.entry_step(), None);
+        assert_eq!(through.velocity(), Vector2::new(17, -4));
+    }
+
+    #[test]
+    fn test_display() {
+        let area: Area = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let expected = "\
+.............#....#............
+.......#..............#........
+...............................
+S........................#.....
+...............................
+...............................
+...........................#...
+...............................
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTT#TT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT";
+
+        assert_eq!(
+            Trajectory::launch(Vector2::new(7, 2), &area).to_string(),
+            expected
+        );
+
+        // The path ends once the probe is past the target area, unlike the puzzle's illustration
+        let miss = "\
+S.................................
+..................................
+..................................
+..................................
+.................#................
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT..#
+....................TTTTTTTTTTT...";
+        assert_eq!(
+            Trajectory::launch(Vector2::new(17, -4), &area).to_string(),
+            miss
+        );
+    }
+
+    #[test]
+    fn test_hits_match_simulation() {
+        let areas = [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=-4..6, y=-8..-3",
+            "target area: x=3..12, y=4..9",
+            "target area: x=-12..-3, y=4..9",
+            "target area: x=140..170, y=-40..-25",
+        ];
 
-        let result = simulate(Point::new(0, 0), Point::new(9, 0), 10, &area);
+        for area in areas {
+            let area: Area = area.parse().unwrap();
+            let bound = area.min().x.abs().max(area.max().x.abs()).max(60);
+            let mut expected = vec![];
+
+            for x in -bound..=bound {
+                for y in -bound..=bound {
+                    if Trajectory::launch(Vector2::new(x, y), &area).is_hit() {
+                        expected.push(Vector2::new(x, y));
+                    }
+                }
+            }
+
+            let mut actual: Vec<_> = hits(&area).unwrap().into_keys().collect();
+            actual.sort_by_key(|v| (v.x, v.y));
+            assert_eq!(actual, expected, "{:?}", area);
+        }
+    }
+
+    #[test]
+    fn test_highest_point_matches_apex() {
+        let area: Area = "target area: x=20..30, y=-10..-5".parse().unwrap();
+
+        assert_eq!(highest_point(-2, 3), 0);
+        assert_eq!(highest_point(0, 5), 0);
+        assert_eq!(highest_point(9, 20), 45);
+
+        let hits = hits(&area).unwrap();
+        assert!(hits.keys().any(|v| v.y < 0));
+        for (velocity, step) in hits {
+            assert_eq!(
+                highest_point(velocity.y, step),
+                Trajectory::launch(velocity, &area).apex().y,
+                "{:?}",
+                velocity
+            );
+        }
+    }
+
+    #[test]
+    fn test_unbounded_area() {
+        let area: Area = "target area: x=20..30, y=-5..5".parse().unwrap();
 
-        assert!(result.is_some());
+        assert!(Day17::star_two(&area).is_err());
     }
 }
@@ -1,22 +1,380 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::{fmt, iter::Peekable, ops::Add, str::Chars, str::FromStr};
+
+use crate::{try_parse_lines, Error, Solution};
+
+/// A snailfish number, a binary tree with regular numbers at the leaves.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Regular(u64),
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+}
+
+/// A single action taken while reducing a snailfish number.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Explode,
+    Split,
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+impl SnailfishNumber {
+    pub fn pair(left: SnailfishNumber, right: SnailfishNumber) -> Self {
+        Self::Pair(Box::new(left), Box::new(right))
+    }
+
+    /// The magnitude, three times the magnitude of the left element plus two times the magnitude
+    /// of the right element of each pair.
+    pub fn magnitude(&self) -> u64 {
+        match self {
+            Self::Regular(value) => *value,
+            Self::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+
+    /// Perform the first applicable reduction action, explosions take precedence over splits.
+    ///
+    /// Returns `None` when the number is fully reduced.
+    pub fn reduce_once(&mut self) -> Option<Action> {
+        if self.explode(0).is_some() {
+            Some(Action::Explode)
+        } else if self.split() {
+            Some(Action::Split)
+        } else {
+            None
+        }
+    }
+
+    /// Apply reduction actions until none apply.
+    pub fn reduce(&mut self) {
+        while self.reduce_once().is_some() {}
+    }
+
+    /// Iterate over every action taken while reducing `self` together with the number after that
+    /// action.
+    pub fn reduction_steps(self) -> ReductionSteps {
+        ReductionSteps {
+            current: Some(self),
+        }
+    }
+
+    /// Explode the leftmost pair nested inside four pairs, returning the values to carry to the
+    /// left and right.
+    fn explode(&mut self, depth: usize) -> Option<(Option<u64>, Option<u64>)> {
+        let (left, right) = match self {
+            Self::Regular(_) => return None,
+            Self::Pair(left, right) => (left, right),
+        };
+
+        if depth >= 4 {
+            if let (Self::Regular(l), Self::Regular(r)) = (left.as_ref(), right.as_ref()) {
+                let carry = (Some(*l), Some(*r));
+                *self = Self::Regular(0);
+
+                return Some(carry);
+            }
+        }
+
+        if let Some((carry_left, carry_right)) = left.explode(depth + 1) {
+            if let Some(value) = carry_right {
+                right.add_to_leftmost(value);
+            }
+
+            return Some((carry_left, None));
+        }
+
+        if let Some((carry_left, carry_right)) = right.explode(depth + 1) {
+            if let Some(value) = carry_left {
+                left.add_to_rightmost(value);
+            }
+
+            return Some((None, carry_right));
+        }
+
+        None
+    }
+
+    /// Split the leftmost regular number that is 10 or greater.
+    fn split(&mut self) -> bool {
+        match self {
+            Self::Regular(value) if *value >= 10 => {
+                *self = Self::pair(
+                    Self::Regular(*value / 2),
+                    Self::Regular(*value - *value / 2),
+                );
+
+                true
+            }
+            Self::Regular(_) => false,
+            Self::Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    fn add_to_leftmost(&mut self, amount: u64) {
+        match self {
+            Self::Regular(value) => *value += amount,
+            Self::Pair(left, _) => left.add_to_leftmost(amount),
+        }
+    }
+
+    fn add_to_rightmost(&mut self, amount: u64) {
+        match self {
+            Self::Regular(value) => *value += amount,
+            Self::Pair(_, right) => right.add_to_rightmost(amount),
+        }
+    }
+
+    fn parse_element(chars: &mut Peekable<Chars>, s: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| {
+            Error::invalid_input(format!("Invalid snailfish number {}: {}", s, reason))
+        };
+
+        match chars.peek() {
+            Some('[') => {
+                chars.next();
+                let left = Self::parse_element(chars, s)?;
+
+                if chars.next() != Some(',') {
+                    return Err(invalid("expected `,`"));
+                }
+
+                let right = Self::parse_element(chars, s)?;
+
+                if chars.next() != Some(']') {
+                    return Err(invalid("expected `]`"));
+                }
+
+                Ok(Self::pair(left, right))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut value: u64 = 0;
+
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    chars.next();
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|v| v.checked_add(digit.into()))
+                        .ok_or_else(|| invalid("regular number is too large"))?;
+                }
+
+                Ok(Self::Regular(value))
+            }
+            _ => Err(invalid("expected `[` or a digit")),
+        }
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = Error;
+
+    /// Parse a snailfish number, e.g. `[[1,2],3]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars().peekable();
+        let number = Self::parse_element(&mut chars, s)?;
+
+        if chars.next().is_some() {
+            return Err(Error::invalid_input(format!(
+                "Unexpected trailing characters in snailfish number {}",
+                s
+            )));
+        }
+
+        match number {
+            Self::Regular(_) => Err(Error::invalid_input(format!(
+                "Snailfish numbers must be pairs, not true for {}",
+                s
+            ))),
+            pair => Ok(pair),
+        }
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Regular(value) => write!(f, "{}", value),
+            Self::Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+impl fmt::Debug for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<SnailfishNumber {} >", self)
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    /// Add two snailfish numbers by pairing them up and reducing the result.
+    fn add(self, rhs: SnailfishNumber) -> Self::Output {
+        let mut sum = SnailfishNumber::pair(self, rhs);
+        sum.reduce();
+
+        sum
+    }
+}
+
+/// Iterator over the steps of reducing a snailfish number, see
+/// [`SnailfishNumber::reduction_steps`].
+pub struct ReductionSteps {
+    current: Option<SnailfishNumber>,
+}
+
+impl Iterator for ReductionSteps {
+    type Item = (Action, SnailfishNumber);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.as_mut()?;
+
+        match current.reduce_once() {
+            Some(action) => Some((action, current.clone())),
+            None => {
+                self.current = None;
+
+                None
+            }
+        }
+    }
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<SnailfishNumber>;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_lines(input).collect::<Result<_, _>>()?)
+    }
+
+    fn star_one(numbers: &Self::Input) -> Result<Self::One, Error> {
+        numbers
+            .iter()
+            .cloned()
+            .reduce(|acc, number| acc + number)
+            .map(|sum| sum.magnitude())
+            .ok_or_else(|| Error::no_solution("No snailfish numbers to add"))
+    }
+
+    fn star_two(numbers: &Self::Input) -> Result<Self::Two, Error> {
+        numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| {
+                numbers
+                    .iter()
+                    .enumerate()
+                    .filter(move |(j, _)| i != *j)
+                    .map(move |(_, b)| (a.clone() + b.clone()).magnitude())
+            })
+            .max()
+            .ok_or_else(|| Error::no_solution("Need at least two snailfish numbers to add"))
+    }
+}
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day18::star_one(&Day18::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day18::star_two(&Day18::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{Action, Day18, SnailfishNumber};
+    use crate::Solution;
+
+    fn number(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let s = "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]";
+
+        assert_eq!(number(s).to_string(), s);
+        assert!("[1,2".parse::<SnailfishNumber>().is_err());
+        assert!("[1,2]]".parse::<SnailfishNumber>().is_err());
+        assert!("[1;2]".parse::<SnailfishNumber>().is_err());
+        assert!("7".parse::<SnailfishNumber>().is_err());
+    }
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_explode() {
+        let cases = [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let mut n = number(input);
+
+            assert_eq!(n.reduce_once(), Some(Action::Explode));
+            assert_eq!(n.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_reduction_steps() {
+        let sum = SnailfishNumber::pair(number("[[[[4,3],4],4],[7,[[8,4],9]]]"), number("[1,1]"));
+
+        let steps: Vec<_> = sum
+            .reduction_steps()
+            .map(|(action, n)| (action, n.to_string()))
+            .collect();
+
+        assert_eq!(
+            steps,
+            vec![
+                (
+                    Action::Explode,
+                    "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]".to_owned()
+                ),
+                (
+                    Action::Explode,
+                    "[[[[0,7],4],[15,[0,13]]],[1,1]]".to_owned()
+                ),
+                (
+                    Action::Split,
+                    "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]".to_owned()
+                ),
+                (
+                    Action::Split,
+                    "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]".to_owned()
+                ),
+                (
+                    Action::Explode,
+                    "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let numbers = Day18::parse("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]").unwrap();
+        let sum = numbers.into_iter().reduce(|a, b| a + b).unwrap();
+
+        assert_eq!(sum.to_string(), "[[[[5,0],[7,4]],[5,5]],[6,6]]");
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_magnitude() {
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(
+            number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(),
+            3488
+        );
     }
 }
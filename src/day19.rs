@@ -1,22 +1,185 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use crate::{
+    math::Vector3,
+    registration::{align_all, Alignment},
+    Error, Solution,
+};
+
+/// The number of beacons two scanners must share for their reports to be aligned.
+const MIN_OVERLAP: usize = 12;
+
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    id: usize,
+    beacons: Vec<Vector3<i64>>,
+}
+
+fn parse_scanner(report: &str) -> Result<Scanner, Error> {
+    let mut lines = report.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    let header = lines
+        .next()
+        .ok_or_else(|| Error::invalid_input("Empty scanner report"))?;
+    let id = header
+        .strip_prefix("--- scanner ")
+        .and_then(|rest| rest.strip_suffix(" ---"))
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| Error::invalid_input(format!("Invalid scanner header {}", header)))?;
+
+    let beacons = lines.map(str::parse).collect::<Result<_, _>>()?;
+
+    Ok(Scanner { id, beacons })
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+fn align(scanners: &[Scanner]) -> Result<Alignment, Error> {
+    let clouds: Vec<_> = scanners.iter().map(|s| s.beacons.clone()).collect();
+
+    align_all(&clouds, MIN_OVERLAP)
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Scanner>;
+    type One = usize;
+    type Two = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let scanners: Vec<_> = input
+            .replace("\r\n", "\n")
+            .split("\n\n")
+            .filter(|report| !report.trim().is_empty())
+            .map(parse_scanner)
+            .collect::<Result<_, _>>()?;
+
+        if let Some((index, scanner)) = scanners
+            .iter()
+            .enumerate()
+            .find(|(index, scanner)| *index != scanner.id)
+        {
+            return Err(Error::invalid_input(format!(
+                "Expected scanner {} but found scanner {}",
+                index, scanner.id
+            )));
+        }
+
+        Ok(scanners)
+    }
+
+    fn star_one(scanners: &Self::Input) -> Result<Self::One, Error> {
+        Ok(align(scanners)?.points().len())
+    }
+
+    fn star_two(scanners: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(align(scanners)?.max_manhattan_distance())
+    }
+}
+
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day19::star_one(&Day19::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<i64, Error> {
+    Day19::star_two(&Day19::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::Day19;
+    use crate::{math::Vector3, Solution};
+
+    // Three scanners where scanner 0 and 2 only overlap via scanner 1
+    const INPUT: &str = r#"--- scanner 0 ---
+212,-833,447
+-103,115,-113
+-369,-573,-16
+-830,8,70
+798,-792,622
+255,-921,-818
+228,169,471
+555,-1293,-472
+441,183,-349
+-654,-144,545
+367,-223,-20
+97,-1286,404
+244,-1070,27
+812,-73,857
+389,214,-334
+-394,899,400
+276,-770,-260
+-743,-570,-563
+90,-1290,-514
+-348,-294,72
+
+--- scanner 1 ---
+127,960,1156
+517,510,1028
+44,22,-471
+-539,1341,710
+604,492,998
+-1023,299,23
+-711,431,287
+-410,-101,614
+724,180,-89
+-649,198,-432
+-673,-437,27
+-413,144,490
+430,1047,921
+-1415,160,514
+-1429,373,-306
+-318,-24,659
+47,487,-429
+-1361,-171,-70
+7,439,926
+-176,176,70
+-646,-501,-552
+-760,801,-395
+321,68,-621
+347,1301,-155
+659,200,444
+40,29,447
+890,-327,-859
+-1102,-722,588
+-325,187,-775
+
+--- scanner 2 ---
+-24,249,878
+-369,-658,1138
+700,-828,837
+168,-116,77
+704,579,-296
+177,392,298
+570,-89,694
+388,-1427,-264
+747,136,671
+558,-244,527
+471,-351,-10
+765,-1361,857
+-498,-562,-304
+-670,-985,606
+645,-274,545
+-277,-613,1061
+14,-229,709
+48,-346,598"#;
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_scanner_positions() {
+        let scanners = Day19::parse(INPUT).unwrap();
+        let alignment = super::align(&scanners).unwrap();
+
+        assert_eq!(
+            alignment.positions().collect::<Vec<_>>(),
+            vec![
+                Vector3::new(0, 0, 0),
+                Vector3::new(68, -1246, -43),
+                Vector3::new(1105, -1205, 1229)
+            ]
+        );
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_parse_errors() {
+        assert!(Day19::parse("--- scanner 0 ---\n1,2").is_err());
+        assert!(Day19::parse("--- scanner 1 ---\n1,2,3").is_err());
+        assert!(Day19::parse("scanner 0\n1,2,3").is_err());
     }
 }
@@ -1,22 +1,256 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::{fmt, str::FromStr};
+
+use crate::{
+    math::{Grid, Vector2},
+    Error, Solution,
+};
+
+fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+/// The image enhancement algorithm, whether the output pixel is lit for each 9 bit neighbourhood.
+#[derive(Clone)]
+pub struct Algorithm([bool; 512]);
+
+impl Algorithm {
+    fn lookup(&self, index: usize) -> bool {
+        self.0[index]
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut rules = [false; 512];
+
+        if s.chars().count() != rules.len() {
+            return Err(Error::invalid_input(format!(
+                "Expected {} rules in the enhancement algorithm, found {}",
+                rules.len(),
+                s.chars().count()
+            )));
+        }
+
+        for (rule, c) in rules.iter_mut().zip(s.chars()) {
+            *rule = parse_pixel(c).ok_or_else(|| {
+                Error::invalid_input(format!("Invalid pixel `{}` in enhancement algorithm", c))
+            })?;
+        }
+
+        Ok(Self(rules))
+    }
+}
+
+impl fmt::Debug for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: String = self.0.iter().map(|&p| if p { '#' } else { '.' }).collect();
+
+        write!(f, "<Algorithm {} >", rules)
+    }
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+/// An infinite image, a finite grid of pixels surrounded by pixels that all have the same
+/// background value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
+
+impl Image {
+    /// The pixel at `(x, y)` relative to the top left of the finite part, anything outside it
+    /// is background.
+    fn pixel(&self, x: i64, y: i64) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+
+        self.pixels
+            .get(Vector2::new(x as usize, y as usize))
+            .copied()
+            .unwrap_or(self.background)
+    }
+
+    /// Enhance the image once, the finite part grows by one pixel on each side since those are the
+    /// only pixels outside it whose neighbourhood isn't all background.
+    pub fn enhance(&self, algorithm: &Algorithm) -> Image {
+        let pixels = Grid::from_fn(self.pixels.width() + 2, self.pixels.height() + 2, |p| {
+            let (x, y) = (p.x as i64 - 1, p.y as i64 - 1);
+
+            let index = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .fold(0, |acc, (dx, dy)| {
+                    (acc << 1) | usize::from(self.pixel(x + dx, y + dy))
+                });
+
+            algorithm.lookup(index)
+        });
+
+        let background = algorithm.lookup(if self.background { 511 } else { 0 });
+
+        Image { pixels, background }
+    }
+
+    /// Iterate over the successive enhancements of this image, starting with the first one.
+    pub fn enhancements(self, algorithm: &Algorithm) -> Enhancements<'_> {
+        Enhancements {
+            current: self,
+            algorithm,
+        }
+    }
+
+    /// The number of lit pixels, `None` when the background is lit and there are infinitely many.
+    pub fn lit_count(&self) -> Option<usize> {
+        (!self.background).then(|| self.pixels.values().filter(|&&p| p).count())
+    }
+
+    pub fn background(&self) -> bool {
+        self.background
+    }
+}
+
+impl FromStr for Image {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            pixels: Grid::parse_with(s, parse_pixel)?,
+            background: false,
+        })
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pixels.map(|&p| if p { '#' } else { '.' }))
+    }
+}
+
+/// Iterator over successive enhancements of an image, see [`Image::enhancements`].
+pub struct Enhancements<'a> {
+    current: Image,
+    algorithm: &'a Algorithm,
+}
+
+impl Iterator for Enhancements<'_> {
+    type Item = Image;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current = self.current.enhance(self.algorithm);
+
+        Some(self.current.clone())
+    }
+}
+
+/// The number of lit pixels after enhancing `image` `steps` times.
+pub fn lit_after(algorithm: &Algorithm, image: &Image, steps: usize) -> Result<usize, Error> {
+    let enhanced = match steps {
+        0 => image.clone(),
+        _ => image
+            .clone()
+            .enhancements(algorithm)
+            .nth(steps - 1)
+            .expect("Enhancements never end"),
+    };
+
+    enhanced.lit_count().ok_or_else(|| {
+        Error::no_solution(format!(
+            "Infinitely many pixels are lit after {} enhancements",
+            steps
+        ))
+    })
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = (Algorithm, Image);
+    type One = usize;
+    type Two = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let input = input.trim_start();
+        let (algorithm, image) = input
+            .split_once("\n\n")
+            .or_else(|| input.split_once("\r\n\r\n"))
+            .ok_or_else(|| {
+                Error::invalid_input("Expected the algorithm and image separated by a blank line")
+            })?;
+
+        Ok((algorithm.parse()?, image.parse()?))
+    }
+
+    fn star_one((algorithm, image): &Self::Input) -> Result<Self::One, Error> {
+        lit_after(algorithm, image, 2)
+    }
+
+    fn star_two((algorithm, image): &Self::Input) -> Result<Self::Two, Error> {
+        lit_after(algorithm, image, 50)
+    }
+}
+
+pub fn star_one(input: &str) -> Result<usize, Error> {
+    Day20::star_one(&Day20::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<usize, Error> {
+    Day20::star_two(&Day20::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{lit_after, Algorithm, Day20, Image};
+    use crate::Solution;
+
+    const INPUT: &str = r#"..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###"#;
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_enhancements() {
+        let (algorithm, image) = Day20::parse(INPUT).unwrap();
+
+        let first = image.enhancements(&algorithm).next().unwrap();
+
+        assert_eq!(
+            first.to_string(),
+            r#".##.##.
+#..#.#.
+##.#..#
+####..#
+.#..##.
+..##..#
+...#.#."#
+        );
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_flipping_background() {
+        // Only an all dark neighbourhood lights a pixel, so the background alternates
+        let algorithm: Algorithm = format!("#{}", ".".repeat(511)).parse().unwrap();
+        let image: Image = ".".parse().unwrap();
+
+        let mut steps = image.clone().enhancements(&algorithm);
+        let first = steps.next().unwrap();
+        let second = steps.next().unwrap();
+
+        assert!(first.background());
+        assert_eq!(first.lit_count(), None);
+        assert_eq!(first.to_string(), "###\n###\n###");
+        assert!(!second.background());
+        assert_eq!(second.lit_count(), Some(0));
+        assert!(lit_after(&algorithm, &image, 1).is_err());
+        assert_eq!(lit_after(&algorithm, &image, 0), Ok(0));
     }
 }
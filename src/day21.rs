@@ -1,22 +1,343 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::collections::HashMap;
+
+use crate::{Error, Solution};
+
+/// A die that lands on a single value each time it's rolled.
+pub trait Die {
+    fn roll(&mut self) -> u64;
+}
+
+/// A die that splits the universe every time it's rolled, landing on the same values in the same
+/// number of universes on every roll.
+pub trait QuantumDie {
+    /// Every value the die lands on together with the number of universes in which it does so.
+    fn outcomes(&self) -> Vec<(u64, u64)>;
+}
+
+/// A die that always rolls 1, 2, 3, and so on up to the number of sides and then starts over.
+#[derive(Debug, Clone)]
+pub struct DeterministicDie {
+    sides: u64,
+    rolls: u64,
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+fn validate_sides(sides: u64) -> Result<u64, Error> {
+    if sides == 0 {
+        return Err(Error::invalid_input("A die needs at least one side"));
+    }
+
+    Ok(sides)
+}
+
+impl DeterministicDie {
+    pub fn new(sides: u64) -> Result<Self, Error> {
+        Ok(Self {
+            sides: validate_sides(sides)?,
+            rolls: 0,
+        })
+    }
+
+    /// The number of times the die has been rolled.
+    pub fn rolls(&self) -> u64 {
+        self.rolls
+    }
+}
+
+impl Die for DeterministicDie {
+    fn roll(&mut self) -> u64 {
+        let value = self.rolls % self.sides + 1;
+        self.rolls += 1;
+
+        value
+    }
+}
+
+/// A die that splits the universe into one copy for each of its sides every time it's rolled.
+#[derive(Debug, Clone)]
+pub struct DiracDie {
+    sides: u64,
+}
+
+impl DiracDie {
+    pub fn new(sides: u64) -> Result<Self, Error> {
+        Ok(Self {
+            sides: validate_sides(sides)?,
+        })
+    }
+}
+
+impl QuantumDie for DiracDie {
+    fn outcomes(&self) -> Vec<(u64, u64)> {
+        (1..=self.sides).map(|value| (value, 1)).collect()
+    }
+}
+
+/// The rules of a game of Dirac Dice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub board_size: u64,
+    pub winning_score: u64,
+    pub rolls_per_turn: u32,
+}
+
+/// The end state of a game played in a single universe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Finished {
+    /// The index of the winning player.
+    pub winner: usize,
+    pub scores: [u64; 2],
+}
+
+impl Finished {
+    pub fn losing_score(&self) -> u64 {
+        self.scores[1 - self.winner]
+    }
+}
+
+type Players = ([u64; 2], [u64; 2]);
+
+impl Game {
+    /// The rules of the practice game.
+    pub const PRACTICE: Game = Game {
+        board_size: 10,
+        winning_score: 1000,
+        rolls_per_turn: 3,
+    };
+
+    /// The rules when playing with the Dirac die.
+    pub const DIRAC: Game = Game {
+        board_size: 10,
+        winning_score: 21,
+        rolls_per_turn: 3,
+    };
+
+    fn validate(&self, start: [u64; 2]) -> Result<(), Error> {
+        if self.board_size == 0 || self.winning_score == 0 || self.rolls_per_turn == 0 {
+            return Err(Error::invalid_input(format!("Invalid game {:?}", self)));
+        }
+
+        match start.iter().find(|&&p| p == 0 || p > self.board_size) {
+            Some(p) => Err(Error::invalid_input(format!(
+                "Starting position {} is not on a board of size {}",
+                p, self.board_size
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn advance(&self, position: u64, steps: u64) -> u64 {
+        (position - 1 + steps) % self.board_size + 1
+    }
+
+    /// Play a game in a single universe.
+    pub fn play<D: Die>(&self, start: [u64; 2], die: &mut D) -> Result<Finished, Error> {
+        self.validate(start)?;
+
+        let mut positions = start;
+        let mut scores = [0; 2];
+
+        for player in [0, 1].into_iter().cycle() {
+            let steps: u64 = (0..self.rolls_per_turn).map(|_| die.roll()).sum();
+
+            positions[player] = self.advance(positions[player], steps);
+            scores[player] += positions[player];
+
+            if scores[player] >= self.winning_score {
+                return Ok(Finished {
+                    winner: player,
+                    scores,
+                });
+            }
+        }
+
+        unreachable!("Cycling players never ends")
+    }
+
+    /// Count the number of universes in which each player wins, failing if either count doesn't
+    /// fit in a `u64`.
+    pub fn count_wins<D: QuantumDie>(&self, start: [u64; 2], die: &D) -> Result<[u64; 2], Error> {
+        self.validate(start)?;
+
+        // The possible totals of a turn, with the number of universes each is rolled in
+        let rolls = die.outcomes();
+        let mut turn_outcomes: HashMap<u64, u64> = HashMap::from([(0, 1)]);
+        for _ in 0..self.rolls_per_turn {
+            let mut next: HashMap<u64, u64> = HashMap::new();
+
+            for (total, universes) in &turn_outcomes {
+                for (value, count) in &rolls {
+                    let entry = next.entry(total + value).or_insert(0);
+                    *entry = universes
+                        .checked_mul(*count)
+                        .and_then(|u| entry.checked_add(u))
+                        .ok_or_else(too_many_universes)?;
+                }
+            }
+
+            turn_outcomes = next;
+        }
+
+        let turn_outcomes: Vec<_> = turn_outcomes.into_iter().collect();
+        let mut memo = HashMap::new();
+
+        self.wins((start, [0, 0]), &turn_outcomes, &mut memo)
+    }
+
+    /// The wins of the player about to move and the other player, from the given positions and
+    /// scores where index 0 is the player about to move.
+    fn wins(
+        &self,
+        (positions, scores): Players,
+        turn_outcomes: &[(u64, u64)],
+        memo: &mut HashMap<Players, [u64; 2]>,
+    ) -> Result<[u64; 2], Error> {
+        if let Some(wins) = memo.get(&(positions, scores)) {
+            return Ok(*wins);
+        }
+
+        let mut wins = [0u64, 0];
+        let add = |total: u64, universes: u64, count: u64| {
+            universes
+                .checked_mul(count)
+                .and_then(|u| total.checked_add(u))
+                .ok_or_else(too_many_universes)
+        };
+
+        for &(steps, universes) in turn_outcomes {
+            let position = self.advance(positions[0], steps);
+            let score = scores[0] + position;
+
+            if score >= self.winning_score {
+                wins[0] = add(wins[0], universes, 1)?;
+            } else {
+                // The other player moves next, so swap the perspective
+                let [other, current] = self.wins(
+                    ([positions[1], position], [scores[1], score]),
+                    turn_outcomes,
+                    memo,
+                )?;
+
+                wins[0] = add(wins[0], universes, current)?;
+                wins[1] = add(wins[1], universes, other)?;
+            }
+        }
+
+        memo.insert((positions, scores), wins);
+
+        Ok(wins)
+    }
+}
+
+fn too_many_universes() -> Error {
+    Error::no_solution("The number of universes doesn't fit in a u64")
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = [u64; 2];
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut start = [0; 2];
+        let mut lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        for (player, position) in start.iter_mut().enumerate() {
+            let line = lines.next().ok_or_else(|| {
+                Error::invalid_input(format!(
+                    "Missing starting position of player {}",
+                    player + 1
+                ))
+            })?;
+
+            *position = line
+                .strip_prefix(&format!("Player {} starting position:", player + 1))
+                .and_then(|p| p.trim().parse().ok())
+                .ok_or_else(|| {
+                    Error::invalid_input(format!("Invalid starting position {}", line))
+                })?;
+        }
+
+        match lines.next() {
+            Some(line) => Err(Error::invalid_input(format!("Unexpected line {}", line))),
+            None => Ok(start),
+        }
+    }
+
+    fn star_one(start: &Self::Input) -> Result<Self::One, Error> {
+        let mut die = DeterministicDie::new(100)?;
+        let finished = Game::PRACTICE.play(*start, &mut die)?;
+
+        Ok(finished.losing_score() * die.rolls())
+    }
+
+    fn star_two(start: &Self::Input) -> Result<Self::Two, Error> {
+        let wins = Game::DIRAC.count_wins(*start, &DiracDie::new(3)?)?;
+
+        Ok(wins[0].max(wins[1]))
+    }
+}
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day21::star_one(&Day21::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day21::star_two(&Day21::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{DeterministicDie, DiracDie, Finished, Game};
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_play() {
+        let mut die = DeterministicDie::new(100).unwrap();
+
+        assert_eq!(
+            Game::PRACTICE.play([4, 8], &mut die),
+            Ok(Finished {
+                winner: 0,
+                scores: [1000, 745]
+            })
+        );
+        assert_eq!(die.rolls(), 993);
+        assert!(Game::PRACTICE.play([0, 11], &mut die).is_err());
+    }
+
+    #[test]
+    fn test_count_wins() {
+        assert_eq!(
+            Game::DIRAC.count_wins([4, 8], &DiracDie::new(3).unwrap()),
+            Ok([444356092776315, 341960390180808])
+        );
+
+        // Any first turn wins when the winning score is 1
+        let game = Game {
+            board_size: 10,
+            winning_score: 1,
+            rolls_per_turn: 2,
+        };
+        assert_eq!(
+            game.count_wins([1, 1], &DiracDie::new(6).unwrap()),
+            Ok([36, 0])
+        );
+
+        // Larger games split into more universes than fit in a u64
+        let game = Game {
+            board_size: 10,
+            winning_score: 40,
+            rolls_per_turn: 3,
+        };
+        assert!(game
+            .count_wins([4, 8], &DiracDie::new(10).unwrap())
+            .is_err());
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_no_sides() {
+        assert!(DeterministicDie::new(0).is_err());
+        assert!(DiracDie::new(0).is_err());
     }
 }
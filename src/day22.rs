@@ -1,22 +1,246 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    math::{Cuboid, Vector3},
+    try_parse_lines, Error, Solution,
+};
+
+/// A single reboot step, turning every cube in a cuboid on or off.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+impl FromStr for Step {
+    type Err = Error;
+
+    /// Parse a step, e.g. `on x=10..12,y=10..12,z=10..12`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_step = || Error::invalid_input(format!("Invalid step {}", s));
+
+        let (state, ranges) = s.trim().split_once(' ').ok_or_else(invalid_step)?;
+        let on = match state {
+            "on" => true,
+            "off" => false,
+            _ => return Err(invalid_step()),
+        };
+
+        let mut ranges = ranges
+            .split(',')
+            .zip(["x=", "y=", "z="])
+            .map(|(range, axis)| {
+                let (start, end) = range.trim().strip_prefix(axis)?.split_once("..")?;
+
+                Some((start.parse::<i64>().ok()?, end.parse::<i64>().ok()?))
+            });
+
+        let (x, y, z) = match (ranges.next(), ranges.next(), ranges.next(), ranges.next()) {
+            (Some(Some(x)), Some(Some(y)), Some(Some(z)), None) => (x, y, z),
+            _ => return Err(invalid_step()),
+        };
+
+        let cuboid = Cuboid::new(Vector3::new(x.0, y.0, z.0), Vector3::new(x.1, y.1, z.1))
+            .ok_or_else(|| Error::invalid_input(format!("Empty cuboid in step {}", s)))?;
+
+        Ok(Self { on, cuboid })
+    }
+}
+
+/// The reactor core, tracking which cubes are on.
+///
+/// The lit cubes are kept both as signed cuboids, where overlaps are cancelled out by cuboids
+/// with the opposite sign, which makes the volume cheap to compute, and as disjoint cuboids which
+/// make it possible to tell if a given cube is on. A cuboid's sign may be larger than one when it
+/// was added several times.
+#[derive(Debug, Clone, Default)]
+pub struct Reactor {
+    region: Option<Cuboid>,
+    signed: HashMap<Cuboid, i64>,
+    disjoint: Vec<Cuboid>,
+}
+
+impl Reactor {
+    /// The region considered during the initialization procedure.
+    pub const INITIALIZATION_REGION: Cuboid = match Cuboid::new(
+        Vector3 {
+            x: -50,
+            y: -50,
+            z: -50,
+        },
+        Vector3 {
+            x: 50,
+            y: 50,
+            z: 50,
+        },
+    ) {
+        Some(region) => region,
+        None => unreachable!(),
+    };
+
+    /// A reactor where any cube can be turned on.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A reactor that ignores any cubes outside `region`.
+    pub fn with_region(region: Cuboid) -> Self {
+        Self {
+            region: Some(region),
+            ..Self::default()
+        }
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        let cuboid = match self.region {
+            None => step.cuboid,
+            Some(region) => match region.intersection(&step.cuboid) {
+                None => return,
+                Some(cuboid) => cuboid,
+            },
+        };
+
+        let corrections: Vec<_> = self
+            .signed
+            .iter()
+            .filter_map(|(other, sign)| other.intersection(&cuboid).map(|i| (i, -sign)))
+            .collect();
+        for (intersection, sign) in corrections {
+            *self.signed.entry(intersection).or_insert(0) += sign;
+        }
+
+        self.disjoint = self
+            .disjoint
+            .iter()
+            .flat_map(|other| other.subtract(&cuboid))
+            .collect();
+
+        if step.on {
+            *self.signed.entry(cuboid).or_insert(0) += 1;
+            self.disjoint.push(cuboid);
+        }
+
+        // Equal cuboids are merged, so many of them cancel out entirely
+        self.signed.retain(|_, sign| *sign != 0);
+    }
+
+    /// The number of cubes that are on.
+    pub fn volume(&self) -> u64 {
+        let volume: i128 = self
+            .signed
+            .iter()
+            .map(|(cuboid, sign)| cuboid.volume() as i128 * *sign as i128)
+            .sum();
+
+        volume as u64
+    }
+
+    /// Disjoint cuboids covering exactly the cubes that are on.
+    pub fn lit_cuboids(&self) -> &[Cuboid] {
+        &self.disjoint
+    }
+
+    pub fn is_lit(&self, cube: Vector3<i64>) -> bool {
+        self.disjoint.iter().any(|c| c.contains(cube))
+    }
+}
+
+fn reboot(steps: &[Step], mut reactor: Reactor) -> Reactor {
+    for step in steps {
+        reactor.apply(step);
+    }
+
+    reactor
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Step>;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_lines(input).collect::<Result<_, _>>()?)
+    }
+
+    fn star_one(steps: &Self::Input) -> Result<Self::One, Error> {
+        Ok(reboot(steps, Reactor::with_region(Reactor::INITIALIZATION_REGION)).volume())
+    }
+
+    fn star_two(steps: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(reboot(steps, Reactor::new()).volume())
+    }
+}
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day22::star_one(&Day22::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day22::star_two(&Day22::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{reboot, Day22, Reactor, Step};
+    use crate::{math::Vector3, Solution};
+
+    const INPUT: &str = r#"on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10"#;
+
+    #[test]
+    fn test_parse_step() {
+        let step: Step = "off x=-54112..-39298,y=-85059..-49293,z=-27449..7877"
+            .parse()
+            .unwrap();
+
+        assert!(!step.on);
+        assert_eq!(step.cuboid.min(), Vector3::new(-54112, -85059, -27449));
+        assert_eq!(step.cuboid.max(), Vector3::new(-39298, -49293, 7877));
+
+        assert!("toggle x=1..2,y=1..2,z=1..2".parse::<Step>().is_err());
+        assert!("on x=1..2,y=1..2".parse::<Step>().is_err());
+        assert!("on x=2..1,y=1..2,z=1..2".parse::<Step>().is_err());
+        assert!("on y=1..2,x=1..2,z=1..2".parse::<Step>().is_err());
+    }
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_lit_cubes() {
+        let reactor = reboot(&Day22::parse(INPUT).unwrap(), Reactor::new());
+
+        assert!(reactor.is_lit(Vector3::new(10, 10, 10)));
+        assert!(reactor.is_lit(Vector3::new(12, 12, 12)));
+        assert!(!reactor.is_lit(Vector3::new(11, 11, 11)));
+        assert!(!reactor.is_lit(Vector3::new(9, 9, 9)));
+        assert_eq!(
+            reactor
+                .lit_cuboids()
+                .iter()
+                .map(|c| c.volume())
+                .sum::<u64>(),
+            reactor.volume()
+        );
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_huge_cuboids_and_region() {
+        let steps = Day22::parse(
+            r#"on x=-100000..99999,y=-100000..99999,z=-100000..99999
+off x=0..199999,y=0..199999,z=0..199999
+on x=-100..100,y=-100..100,z=-100..100"#,
+        )
+        .unwrap();
+
+        // Eight octants of 100000³ with one turned off, then a 201³ cube turned back on
+        let expected = 7 * 100_000_u64.pow(3) + 101_u64.pow(3);
+
+        assert_eq!(reboot(&steps, Reactor::new()).volume(), expected);
+        assert_eq!(
+            reboot(&steps, Reactor::with_region(Reactor::INITIALIZATION_REGION)).volume(),
+            101_u64.pow(3)
+        );
     }
 }
@@ -1,22 +1,437 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{search, Error, Solution};
+
+const HALLWAY_LENGTH: usize = 11;
+const ROOMS: usize = 4;
+
+/// The rows that are unfolded from the note when the full diagram is used in part two.
+const FOLDED_ROWS: [[Amphipod; ROOMS]; 2] = [
+    [Amphipod::D, Amphipod::C, Amphipod::B, Amphipod::A],
+    [Amphipod::D, Amphipod::B, Amphipod::A, Amphipod::C],
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Amphipod {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Amphipod {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'A' => Some(Self::A),
+            'B' => Some(Self::B),
+            'C' => Some(Self::C),
+            'D' => Some(Self::D),
+            _ => None,
+        }
+    }
+
+    /// The energy required to move one step.
+    pub fn energy(&self) -> u64 {
+        match self {
+            Self::A => 1,
+            Self::B => 10,
+            Self::C => 100,
+            Self::D => 1000,
+        }
+    }
+
+    /// The room this amphipod belongs in.
+    pub fn room(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl fmt::Display for Amphipod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A space in the burrow, rooms are numbered from the left and slots from the hallway down.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Location {
+    Hallway(usize),
+    Room { room: usize, slot: usize },
+}
+
+/// An amphipod moving from one space to another in one go.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub amphipod: Amphipod,
+    pub from: Location,
+    pub to: Location,
+    pub energy: u64,
+}
+
+/// The hallway position just outside a room, amphipods never stop there.
+fn entrance(room: usize) -> usize {
+    2 + room * 2
+}
+
+fn is_entrance(position: usize) -> bool {
+    (0..ROOMS).any(|room| entrance(room) == position)
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Burrow {
+    hallway: [Option<Amphipod>; HALLWAY_LENGTH],
+    rooms: [Vec<Option<Amphipod>>; ROOMS],
+}
+
+impl Burrow {
+    /// The number of amphipods that fit in each room.
+    pub fn depth(&self) -> usize {
+        self.rooms[0].len()
+    }
+
+    /// Insert the two folded rows of the full diagram between the first and second row.
+    pub fn unfold(&self) -> Result<Burrow, Error> {
+        if self.depth() != 2 {
+            return Err(Error::invalid_input(format!(
+                "Only burrows with rooms of depth 2 can be unfolded, not {}",
+                self.depth()
+            )));
+        }
+
+        let mut result = self.clone();
+
+        for (room, occupants) in result.rooms.iter_mut().enumerate() {
+            occupants.splice(1..1, FOLDED_ROWS.iter().map(|row| Some(row[room])));
+        }
+
+        Ok(result)
+    }
+
+    pub fn is_organised(&self) -> bool {
+        self.hallway.iter().all(Option::is_none)
+            && self.rooms.iter().enumerate().all(|(room, occupants)| {
+                occupants.iter().all(|o| o.map(|a| a.room()) == Some(room))
+            })
+    }
+
+    pub fn get(&self, location: Location) -> Option<Amphipod> {
+        match location {
+            Location::Hallway(position) => self.hallway[position],
+            Location::Room { room, slot } => self.rooms[room][slot],
+        }
+    }
+
+    fn set(&mut self, location: Location, amphipod: Option<Amphipod>) {
+        match location {
+            Location::Hallway(position) => self.hallway[position] = amphipod,
+            Location::Room { room, slot } => self.rooms[room][slot] = amphipod,
+        }
+    }
+
+    /// The burrow after making `m`, which must be legal.
+    pub fn apply(&self, m: &Move) -> Burrow {
+        let mut result = self.clone();
+        result.set(m.from, None);
+        result.set(m.to, Some(m.amphipod));
+
+        result
+    }
+
+    /// Whether the hallway is empty between `from` and `to`, not counting `from` itself.
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        let range = if from < to {
+            from + 1..to + 1
+        } else {
+            to..from
+        };
+
+        self.hallway[range].iter().all(Option::is_none)
+    }
+
+    /// Whether the only amphipods in `room` are the ones that belong there.
+    fn accepts(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|amphipod| amphipod.room() == room)
+    }
+
+    /// Every legal move from this state.
+    ///
+    /// Amphipods only leave a room when it or an amphipod below them doesn't belong there, stop in
+    /// the hallway anywhere but outside a room and only enter their own room once it holds no
+    /// strangers.
+    pub fn moves(&self) -> Vec<Move> {
+        let mut result = vec![];
+
+        for (position, amphipod) in self.hallway.iter().enumerate() {
+            let amphipod = match amphipod {
+                Some(a) => *a,
+                None => continue,
+            };
+            let room = amphipod.room();
+
+            if !self.accepts(room) || !self.is_clear(position, entrance(room)) {
+                continue;
+            }
+
+            if let Some(slot) = self.rooms[room].iter().rposition(Option::is_none) {
+                let steps = position.abs_diff(entrance(room)) + slot + 1;
+
+                result.push(Move {
+                    amphipod,
+                    from: Location::Hallway(position),
+                    to: Location::Room { room, slot },
+                    energy: steps as u64 * amphipod.energy(),
+                });
+            }
+        }
+
+        for room in 0..ROOMS {
+            if self.accepts(room) {
+                continue;
+            }
+
+            let (slot, amphipod) = match self.rooms[room]
+                .iter()
+                .enumerate()
+                .find_map(|(slot, a)| a.map(|a| (slot, a)))
+            {
+                Some(top) => top,
+                None => continue,
+            };
+
+            for position in (0..HALLWAY_LENGTH).filter(|p| !is_entrance(*p)) {
+                if !self.is_clear(entrance(room), position) {
+                    continue;
+                }
+
+                let steps = slot + 1 + position.abs_diff(entrance(room));
+
+                result.push(Move {
+                    amphipod,
+                    from: Location::Room { room, slot },
+                    to: Location::Hallway(position),
+                    energy: steps as u64 * amphipod.energy(),
+                });
+            }
+        }
+
+        result
+    }
+}
+
+impl FromStr for Burrow {
+    type Err = Error;
+
+    /// Parse a diagram of the burrow, e.g.
+    ///
+    /// ```text
+    /// #############
+    /// #...........#
+    /// ###B#C#B#D###
+    ///   #A#D#C#A#
+    ///   #########
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_space = |c: char| match c {
+            '.' => Ok(None),
+            c => Amphipod::from_char(c)
+                .map(Some)
+                .ok_or_else(|| Error::invalid_input(format!("Invalid space `{}` in burrow", c))),
+        };
+
+        let lines: Vec<Vec<char>> = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.chars().collect())
+            .collect();
+
+        let hallway_line = lines
+            .get(1)
+            .filter(|l| l.len() >= HALLWAY_LENGTH + 2)
+            .ok_or_else(|| Error::invalid_input("Missing hallway in burrow"))?;
+        let mut hallway = [None; HALLWAY_LENGTH];
+        for (position, space) in hallway.iter_mut().enumerate() {
+            *space = parse_space(hallway_line[position + 1])?;
+        }
+
+        let mut rooms: [Vec<Option<Amphipod>>; ROOMS] = Default::default();
+        for line in lines.iter().skip(2) {
+            if line.iter().all(|c| c.is_whitespace() || *c == '#') {
+                continue;
+            }
+
+            for (room, occupants) in rooms.iter_mut().enumerate() {
+                let c = line.get(entrance(room) + 1).ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "Room row {} is too short",
+                        line.iter().collect::<String>()
+                    ))
+                })?;
+
+                occupants.push(parse_space(*c)?);
+            }
+        }
+
+        if rooms[0].is_empty() {
+            return Err(Error::invalid_input("Burrow has no rooms"));
+        }
+
+        let mut counts: HashMap<Amphipod, usize> = HashMap::new();
+        for amphipod in hallway.iter().chain(rooms.iter().flatten()).flatten() {
+            *counts.entry(*amphipod).or_insert(0) += 1;
+        }
+        if let Some((amphipod, count)) = counts.iter().find(|(_, c)| **c != rooms[0].len()) {
+            return Err(Error::invalid_input(format!(
+                "Expected {} amphipods of type {}, found {}",
+                rooms[0].len(),
+                amphipod,
+                count
+            )));
+        }
+
+        Ok(Self { hallway, rooms })
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let space = |a: Option<Amphipod>| a.map_or(".".to_owned(), |a| a.to_string());
+
+        writeln!(f, "{}", "#".repeat(HALLWAY_LENGTH + 2))?;
+        write!(f, "#")?;
+        for a in &self.hallway {
+            write!(f, "{}", space(*a))?;
+        }
+        writeln!(f, "#")?;
+
+        for slot in 0..self.depth() {
+            let (edge, end) = if slot == 0 {
+                ("###", "###")
+            } else {
+                ("  #", "#")
+            };
+
+            write!(f, "{}", edge)?;
+            for room in &self.rooms {
+                write!(f, "{}#", space(room[slot]))?;
+            }
+            writeln!(f, "{}", &end[1..])?;
+        }
+
+        write!(f, "  {}", "#".repeat(ROOMS * 2 + 1))
+    }
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+impl fmt::Debug for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Burrow\n{}\n>", self)
+    }
+}
+
+/// The cheapest way to organise a burrow.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub energy: u64,
+    /// Every move together with the burrow after making it.
+    pub steps: Vec<(Move, Burrow)>,
+}
+
+/// Find the cheapest sequence of moves that organises the burrow.
+pub fn organise(burrow: &Burrow) -> Result<Plan, Error> {
+    let path = search::dijkstra(
+        burrow.clone(),
+        |b| {
+            b.moves()
+                .into_iter()
+                .map(|m| (m, b.apply(&m), m.energy))
+                .collect::<Vec<_>>()
+        },
+        Burrow::is_organised,
+    )
+    .ok_or_else(|| Error::no_solution("The amphipods can't be organised"))?;
+
+    Ok(Plan {
+        energy: path.cost,
+        steps: path.steps,
+    })
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Burrow;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input.parse()
+    }
+
+    fn star_one(burrow: &Self::Input) -> Result<Self::One, Error> {
+        Ok(organise(burrow)?.energy)
+    }
+
+    fn star_two(burrow: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(organise(&burrow.unfold()?)?.energy)
+    }
+}
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day23::star_one(&Day23::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day23::star_two(&Day23::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{organise, Burrow, Day23};
+    use crate::Solution;
+
+    const INPUT: &str = r#"#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########"#;
 
     #[test]
-    fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+    fn test_parse_and_display() {
+        let burrow = Day23::parse(INPUT).unwrap();
+
+        assert_eq!(burrow.to_string(), INPUT);
+        assert_eq!(
+            burrow.unfold().unwrap().to_string(),
+            r#"#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########"#
+        );
+        assert!(burrow.unfold().unwrap().unfold().is_err());
+        assert!(INPUT.replace('D', "A").parse::<Burrow>().is_err());
     }
 
     #[test]
-    fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+    fn test_replay_plan() {
+        let burrow = Day23::parse(INPUT).unwrap();
+        let plan = organise(&burrow).unwrap();
+
+        let mut current = burrow;
+        for (m, after) in &plan.steps {
+            assert!(current.moves().contains(m));
+            current = current.apply(m);
+            assert_eq!(&current, after);
+        }
+
+        assert!(current.is_organised());
+        assert_eq!(
+            plan.steps.iter().map(|(m, _)| m.energy).sum::<u64>(),
+            plan.energy
+        );
     }
 }
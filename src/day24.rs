@@ -1,22 +1,552 @@
-pub fn star_one(input: &str) -> i64 {
-    0
+use std::{fmt, str::FromStr};
+
+use crate::{try_parse_lines, DigitIterator, Error, Solution};
+
+/// The number of digits in a model number, the MONAD program has one block per digit.
+const MODEL_NUMBER_DIGITS: usize = 14;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl FromStr for Register {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w" => Ok(Self::W),
+            "x" => Ok(Self::X),
+            "y" => Ok(Self::Y),
+            "z" => Ok(Self::Z),
+            _ => Err(Error::invalid_input(format!("Invalid register {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::W => "w",
+            Self::X => "x",
+            Self::Y => "y",
+            Self::Z => "z",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Literal(i64),
+}
+
+impl FromStr for Operand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(literal) => Ok(Self::Literal(literal)),
+            Err(_) => Ok(Self::Register(s.parse()?)),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Register(register) => write!(f, "{}", register),
+            Self::Literal(literal) => write!(f, "{}", literal),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    /// Parse an instruction, e.g. `add x -12`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split_whitespace().collect();
+
+        match parts.as_slice() {
+            ["inp", a] => Ok(Self::Inp(a.parse()?)),
+            [op, a, b] => {
+                let (a, b) = (a.parse()?, b.parse()?);
+
+                match *op {
+                    "add" => Ok(Self::Add(a, b)),
+                    "mul" => Ok(Self::Mul(a, b)),
+                    "div" => Ok(Self::Div(a, b)),
+                    "mod" => Ok(Self::Mod(a, b)),
+                    "eql" => Ok(Self::Eql(a, b)),
+                    _ => Err(Error::invalid_input(format!("Invalid instruction {}", s))),
+                }
+            }
+            _ => Err(Error::invalid_input(format!("Invalid instruction {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inp(a) => write!(f, "inp {}", a),
+            Self::Add(a, b) => write!(f, "add {} {}", a, b),
+            Self::Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Self::Div(a, b) => write!(f, "div {} {}", a, b),
+            Self::Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Self::Eql(a, b) => write!(f, "eql {} {}", a, b),
+        }
+    }
+}
+
+/// The arithmetic logic unit, four registers that all start at zero.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Literal(literal) => literal,
+        }
+    }
+
+    /// Run `program`, reading each `inp` from `inputs`.
+    pub fn run(
+        &mut self,
+        program: &[Instruction],
+        inputs: impl IntoIterator<Item = i64>,
+    ) -> Result<(), Error> {
+        let mut inputs = inputs.into_iter();
+
+        for instruction in program {
+            let (a, result) = match *instruction {
+                Instruction::Inp(a) => (
+                    a,
+                    inputs.next().ok_or_else(|| {
+                        Error::invalid_input(format!("No input left for `{}`", instruction))
+                    })?,
+                ),
+                Instruction::Add(a, b) => (a, self.get(a) + self.value(b)),
+                Instruction::Mul(a, b) => (a, self.get(a) * self.value(b)),
+                Instruction::Div(a, b) => match self.value(b) {
+                    0 => {
+                        return Err(Error::invalid_input(format!(
+                            "`{}` divides by zero",
+                            instruction
+                        )))
+                    }
+                    b => (a, self.get(a) / b),
+                },
+                Instruction::Mod(a, b) => match (self.get(a), self.value(b)) {
+                    (a_value, b) if a_value >= 0 && b > 0 => (a, a_value % b),
+                    _ => {
+                        return Err(Error::invalid_input(format!(
+                            "`{}` with a negative or zero operand",
+                            instruction
+                        )))
+                    }
+                },
+                Instruction::Eql(a, b) => (a, i64::from(self.get(a) == self.value(b))),
+            };
+
+            self.registers[a.index()] = result;
+        }
+
+        Ok(())
+    }
+}
+
+/// The parameters of one of the MONAD blocks that each read a single digit `w`.
+///
+/// Treating `z` as a stack of base 26 digits, every block does:
+///
+/// ```text
+/// x = top(z) + check
+/// if divides: pop(z)
+/// if x != w: push(z, w + offset)
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub divides: bool,
+    pub check: i64,
+    pub offset: i64,
+}
+
+impl Block {
+    /// The instructions every block consists of, with the parameters at the marked positions.
+    const TEMPLATE: [&'static str; 18] = [
+        "inp w", "mul x 0", "add x z", "mod x 26", "div z _", "add x _", "eql x w", "eql x 0",
+        "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y _",
+        "mul y x", "add z y",
+    ];
+
+    /// Extract the parameters of a block, which must match the usual MONAD block.
+    pub fn from_instructions(instructions: &[Instruction]) -> Result<Self, Error> {
+        let unexpected = |index: usize| {
+            Error::invalid_input(format!(
+                "Unexpected instruction `{}` in block, expected `{}`",
+                instructions[index],
+                Self::TEMPLATE[index]
+            ))
+        };
+
+        if instructions.len() != Self::TEMPLATE.len() {
+            return Err(Error::invalid_input(format!(
+                "Expected blocks of {} instructions, found {}",
+                Self::TEMPLATE.len(),
+                instructions.len()
+            )));
+        }
+
+        let mut parameters = vec![];
+        for (index, (instruction, expected)) in instructions.iter().zip(Self::TEMPLATE).enumerate()
+        {
+            let text = instruction.to_string();
+
+            match expected.strip_suffix('_') {
+                Some(prefix) => match (text.strip_prefix(prefix), instruction) {
+                    (Some(_), Instruction::Div(_, Operand::Literal(value)))
+                    | (Some(_), Instruction::Add(_, Operand::Literal(value))) => {
+                        parameters.push(*value)
+                    }
+                    _ => return Err(unexpected(index)),
+                },
+                None if text == expected => {}
+                None => return Err(unexpected(index)),
+            }
+        }
+
+        let divides = match parameters[0] {
+            1 => false,
+            26 => true,
+            _ => return Err(unexpected(4)),
+        };
+
+        Ok(Self {
+            divides,
+            check: parameters[1],
+            offset: parameters[2],
+        })
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pop = if self.divides { "pop(z); " } else { "" };
+
+        write!(
+            f,
+            "x = top(z) + {}; {}if x != w {{ push(z, w + {}) }}",
+            self.check, pop, self.offset
+        )
+    }
 }
 
-pub fn star_two(input: &str) -> i64 {
-    0
+/// Split a program into blocks that each start with an `inp` instruction.
+pub fn split_blocks(program: &[Instruction]) -> Result<Vec<Block>, Error> {
+    let starts: Vec<_> = program
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| matches!(i, Instruction::Inp(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    if starts.first() != Some(&0) {
+        return Err(Error::invalid_input("Program must start with `inp`"));
+    }
+
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&program.len()]))
+        .map(|(&start, &end)| Block::from_instructions(&program[start..end]))
+        .collect()
+}
+
+/// The largest and smallest inputs accepted by a program made of `blocks`.
+///
+/// A block that doesn't divide can never match its check since the check is larger than any
+/// digit, so it always pushes. For `z` to end up as zero every block that divides must then avoid
+/// pushing, which relates its digit to the digit that pushed the value it pops:
+/// `digit = pushed_digit + pushed_offset + check`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ModelNumbers {
+    pub largest: u64,
+    pub smallest: u64,
+}
+
+pub fn model_numbers(blocks: &[Block]) -> Result<ModelNumbers, Error> {
+    let mut largest = vec![0_i64; blocks.len()];
+    let mut smallest = vec![0_i64; blocks.len()];
+    let mut stack = vec![];
+
+    for (index, block) in blocks.iter().enumerate() {
+        if !block.divides {
+            if block.check < 10 {
+                return Err(Error::invalid_input(format!(
+                    "Block {} ({}) might not push",
+                    index, block
+                )));
+            }
+
+            stack.push((index, block.offset));
+            continue;
+        }
+
+        let (pushed, offset) = stack.pop().ok_or_else(|| {
+            Error::no_solution(format!("Block {} ({}) pops an empty stack", index, block))
+        })?;
+        let difference = offset + block.check;
+
+        if difference.abs() > 8 {
+            return Err(Error::no_solution(format!(
+                "Digits {} and {} can't differ by {}",
+                pushed, index, difference
+            )));
+        }
+
+        largest[pushed] = 9 - difference.max(0);
+        largest[index] = largest[pushed] + difference;
+        smallest[pushed] = 1 - difference.min(0);
+        smallest[index] = smallest[pushed] + difference;
+    }
+
+    if !stack.is_empty() {
+        return Err(Error::no_solution(format!(
+            "{} blocks push values that are never popped",
+            stack.len()
+        )));
+    }
+
+    let to_number = |digits: &[i64]| {
+        DigitIterator::from_digits(digits.iter().map(|&d| d as u64), 10).ok_or_else(|| {
+            Error::invalid_input(format!("{} digit model numbers don't fit", digits.len()))
+        })
+    };
+
+    Ok(ModelNumbers {
+        largest: to_number(&largest)?,
+        smallest: to_number(&smallest)?,
+    })
+}
+
+/// Whether running `program` on the digits of `number` leaves zero in `z`.
+pub fn is_valid(program: &[Instruction], number: u64) -> Result<bool, Error> {
+    let mut alu = Alu::default();
+    alu.run(program, DigitIterator::new(number).rev().map(|d| d as i64))?;
+
+    Ok(alu.get(Register::Z) == 0)
+}
+
+fn solve(program: &[Instruction]) -> Result<ModelNumbers, Error> {
+    let blocks = split_blocks(program)?;
+
+    if blocks.len() != MODEL_NUMBER_DIGITS {
+        return Err(Error::invalid_input(format!(
+            "Expected {} blocks in MONAD, found {}",
+            MODEL_NUMBER_DIGITS,
+            blocks.len()
+        )));
+    }
+
+    let numbers = model_numbers(&blocks)?;
+
+    // Double check the analysis against the real thing
+    for number in [numbers.largest, numbers.smallest] {
+        if !is_valid(program, number)? {
+            return Err(Error::no_solution(format!(
+                "MONAD rejects the derived model number {}",
+                number
+            )));
+        }
+    }
+
+    Ok(numbers)
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<Instruction>;
+    type One = u64;
+    type Two = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(try_parse_lines(input).collect::<Result<_, _>>()?)
+    }
+
+    fn star_one(program: &Self::Input) -> Result<Self::One, Error> {
+        Ok(solve(program)?.largest)
+    }
+
+    fn star_two(program: &Self::Input) -> Result<Self::Two, Error> {
+        Ok(solve(program)?.smallest)
+    }
+}
+
+pub fn star_one(input: &str) -> Result<u64, Error> {
+    Day24::star_one(&Day24::parse(input)?)
+}
+
+pub fn star_two(input: &str) -> Result<u64, Error> {
+    Day24::star_two(&Day24::parse(input)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{star_one, star_two};
+    use super::{
+        is_valid, model_numbers, split_blocks, star_one, star_two, Alu, Block, Day24, Register,
+    };
+    use crate::Solution;
+
+    /// Build a MONAD like program from `(divisor, check, offset)` for each block.
+    fn program(blocks: &[(i64, i64, i64)]) -> String {
+        blocks
+            .iter()
+            .flat_map(|(divisor, check, offset)| {
+                let mut parameters = [divisor, check, offset].into_iter();
+
+                Block::TEMPLATE.map(|line| match line.strip_suffix('_') {
+                    Some(prefix) => format!("{}{}", prefix, parameters.next().unwrap()),
+                    None => line.to_owned(),
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    const BLOCKS: [(i64, i64, i64); 14] = [
+        (1, 11, 6),
+        (1, 13, 14),
+        (1, 15, 14),
+        (26, -8, 10),
+        (1, 13, 9),
+        (1, 15, 12),
+        (26, -11, 8),
+        (26, -4, 13),
+        (26, -15, 12),
+        (1, 14, 6),
+        (1, 14, 9),
+        (26, -1, 15),
+        (26, -8, 4),
+        (26, -14, 10),
+    ];
 
     #[test]
     fn test_star_one() {
-        assert_eq!(star_one(""), 1)
+        assert_eq!(star_one(&program(&BLOCKS)), Ok(99394899891971));
     }
 
     #[test]
     fn test_star_two() {
-        assert_eq!(star_two(""), 1)
+        assert_eq!(star_two(&program(&BLOCKS)), Ok(92171126131911));
+    }
+
+    #[test]
+    fn test_alu() {
+        let negate = Day24::parse("inp x\nmul x -1").unwrap();
+        let mut alu = Alu::default();
+        alu.run(&negate, [7]).unwrap();
+        assert_eq!(alu.get(Register::X), -7);
+
+        let is_three_times = Day24::parse("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        let mut alu = Alu::default();
+        alu.run(&is_three_times, [3, 9]).unwrap();
+        assert_eq!(alu.get(Register::Z), 1);
+
+        let binary = Day24::parse(
+            r#"inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2"#,
+        )
+        .unwrap();
+        let mut alu = Alu::default();
+        alu.run(&binary, [11]).unwrap();
+        assert_eq!(
+            [Register::W, Register::X, Register::Y, Register::Z].map(|r| alu.get(r)),
+            [1, 0, 1, 1]
+        );
+
+        assert!(Alu::default().run(&negate, []).is_err());
+        assert!(Alu::default()
+            .run(&Day24::parse("div x 0").unwrap(), [])
+            .is_err());
+        assert!(Day24::parse("sub x 1").is_err());
+        assert!(Day24::parse("add q 1").is_err());
+    }
+
+    #[test]
+    fn test_split_blocks() {
+        let instructions = Day24::parse(&program(&BLOCKS)).unwrap();
+        let blocks = split_blocks(&instructions).unwrap();
+
+        assert_eq!(blocks.len(), 14);
+        assert_eq!(
+            blocks[3],
+            Block {
+                divides: true,
+                check: -8,
+                offset: 10
+            }
+        );
+        assert_eq!(
+            blocks[3].to_string(),
+            "x = top(z) + -8; pop(z); if x != w { push(z, w + 10) }"
+        );
+
+        let mut broken = instructions.clone();
+        broken.swap(1, 2);
+        assert!(split_blocks(&broken).is_err());
+    }
+
+    #[test]
+    fn test_model_numbers_match_brute_force() {
+        let blocks = [(1, 12, 7), (1, 11, 3), (26, -5, 1), (26, -9, 2)];
+        let instructions = Day24::parse(&program(&blocks)).unwrap();
+
+        let valid: Vec<u64> = (1111..=9999)
+            .filter(|n: &u64| !n.to_string().contains('0'))
+            .filter(|n| is_valid(&instructions, *n).unwrap())
+            .collect();
+        let numbers = model_numbers(&split_blocks(&instructions).unwrap()).unwrap();
+
+        assert_eq!(numbers.largest, *valid.last().unwrap());
+        assert_eq!(numbers.smallest, valid[0]);
     }
 }
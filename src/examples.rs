@@ -0,0 +1,174 @@
+//! The puzzle examples each day is tested against.
+//!
+//! Examples live in `examples/dayNN/<name>.txt`, any number per day. Each file starts with the
+//! expected answers, in the same format as [`answers`](crate::answers), followed by a `---` line
+//! and then the example input verbatim:
+//!
+//! ```text
+//! one = 7
+//! two = 5
+//! ---
+//! 199
+//! 200
+//! ```
+//!
+//! A part without an expected answer isn't checked, since some examples only apply to one part.
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{answers, Answer, Day, Error, Part};
+
+/// The directory examples are kept in, relative to the crate root.
+pub const EXAMPLES_DIR: &str = "examples";
+
+const SEPARATOR: &str = "---";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub one: Option<Answer>,
+    pub two: Option<Answer>,
+}
+
+impl Example {
+    pub fn parse(name: impl Into<String>, contents: &str) -> Result<Self, Error> {
+        let name = name.into();
+        let (header, input) = match contents.split_once(&format!("\n{}\n", SEPARATOR)) {
+            Some(parts) => parts,
+            None => contents
+                .strip_suffix(&format!("\n{}", SEPARATOR))
+                .map(|header| (header, ""))
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "Example {} has no `{}` line before the input",
+                        name, SEPARATOR
+                    ))
+                })?,
+        };
+
+        let mut example = Self {
+            name,
+            input: input.to_owned(),
+            one: None,
+            two: None,
+        };
+
+        for line in header.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let invalid = || {
+                Error::invalid_input(format!(
+                    "Invalid line in example {}: {}",
+                    example.name, line
+                ))
+            };
+            let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+            let answer = answers::parse_value(value.trim()).ok_or_else(invalid)?;
+
+            let expected = match key.trim() {
+                "one" => &mut example.one,
+                "two" => &mut example.two,
+                _ => return Err(invalid()),
+            };
+            if expected.replace(answer).is_some() {
+                return Err(invalid());
+            }
+        }
+
+        Ok(example)
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.one.as_ref(),
+            Part::Two => self.two.as_ref(),
+        }
+    }
+
+    /// Solve the example with `day`, describing every part that doesn't give the expected answer.
+    pub fn check(&self, day: &Day) -> Result<(), Vec<String>> {
+        let failures: Vec<_> = [Part::One, Part::Two]
+            .into_iter()
+            .filter_map(|part| {
+                let expected = self.expected(part)?;
+
+                match day.solve(part, &self.input) {
+                    Ok(actual) if actual == *expected => None,
+                    Ok(actual) => Some(format!(
+                        "Example {} part {}: expected {} got {}",
+                        self.name, part, expected, actual
+                    )),
+                    Err(e) => Some(format!("Example {} part {}: {}", self.name, part, e)),
+                }
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
+
+/// The examples for `day` in `dir`, ordered by name.
+pub fn load(dir: impl AsRef<Path>, day: u8) -> Result<Vec<Example>, Box<dyn std::error::Error>> {
+    let day_dir = dir.as_ref().join(format!("day{:02}", day));
+    let entries = match fs::read_dir(&day_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|e| e == "txt"));
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            Ok(Example::parse(name, &fs::read_to_string(&path)?)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Example;
+    use crate::{find_day, Answer, Part};
+
+    #[test]
+    fn test_parse() {
+        let example = Example::parse("small", "one = 7\n\n---\n199\n---\n200\n").unwrap();
+
+        assert_eq!(example.expected(Part::One), Some(&Answer::Number(7)));
+        assert_eq!(example.expected(Part::Two), None);
+        assert_eq!(example.input, "199\n---\n200\n");
+
+        let empty = Example::parse("empty", "two = \"AB\"\n---").unwrap();
+        assert_eq!(empty.two, Some(Answer::Text("AB".to_owned())));
+        assert_eq!(empty.input, "");
+
+        assert!(Example::parse("none", "one = 7\n199").is_err());
+        assert!(Example::parse("key", "three = 7\n---\n199").is_err());
+        assert!(Example::parse("twice", "one = 7\none = 8\n---\n199").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let day = find_day(1).unwrap();
+
+        let passing = Example::parse("passing", "one = 3\n---\n199\n200\n208\n210").unwrap();
+        assert_eq!(passing.check(day), Ok(()));
+
+        let failing = Example::parse("failing", "one = 4\ntwo = 0\n---\n199\n200\nx").unwrap();
+        assert_eq!(failing.check(day).unwrap_err().len(), 2);
+    }
+}
@@ -0,0 +1,534 @@
+//! Puzzle input acquisition.
+//!
+//! Inputs are looked up in a cache directory as `dayNN.txt` and, when missing, fetched with a
+//! [`Fetch`] implementation and stored in the cache. Nothing is fetched unless a fetcher is
+//! configured, which [`Inputs::from_env`] only does when `AOC_SESSION` is set, so the tests never
+//! touch the network.
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// The year whose inputs are fetched.
+pub const YEAR: u16 = 2021;
+
+/// How long connecting, and each read or write, may take before fetching gives up.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    /// The input isn't cached and there's no fetcher to get it with.
+    NotCached(PathBuf),
+    /// The server responded with something other than `200 OK`.
+    Status {
+        status: u16,
+        body: String,
+    },
+    /// The server's response couldn't be understood.
+    InvalidResponse(String),
+    /// The base URL isn't one that can be fetched from.
+    UnsupportedUrl(String),
+    /// The session cookie contains characters that can't be sent in a header.
+    InvalidSession,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "{}", e),
+            InputError::NotCached(path) => write!(
+                f,
+                "{} doesn't exist, set AOC_SESSION to download it",
+                path.display()
+            ),
+            InputError::Status { status, body } => {
+                write!(f, "Server responded with {}: {}", status, body.trim())
+            }
+            InputError::InvalidResponse(message) => write!(f, "Invalid response: {}", message),
+            InputError::UnsupportedUrl(url) => write!(f, "Unsupported URL {}", url),
+            InputError::InvalidSession => write!(f, "The session can't contain line breaks"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+/// A way of getting the input for a day that isn't cached yet.
+pub trait Fetch {
+    fn fetch(&self, day: u8) -> Result<String, InputError>;
+}
+
+/// Fetches inputs over HTTP from a server laid out like the Advent of Code website.
+///
+/// `http://` URLs are requested directly, `https://` ones via `curl` since there's no TLS
+/// implementation in the standard library.
+#[derive(Clone)]
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+}
+
+impl HttpFetcher {
+    pub const DEFAULT_BASE_URL: &'static str = "https://adventofcode.com";
+
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            session: session.into(),
+        }
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+
+    fn fetch_plain(&self, url: &str) -> Result<String, InputError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| InputError::UnsupportedUrl(url.to_owned()))?;
+        let (host, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_owned()
+        } else {
+            format!("{}:80", host)
+        };
+
+        let address = address.to_socket_addrs()?.next().ok_or_else(|| {
+            InputError::InvalidResponse(format!("{} didn't resolve to an address", host))
+        })?;
+        let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        // HTTP/1.0 keeps the server from using chunked encoding and closes the connection once
+        // the body has been sent.
+        write!(
+            stream,
+            "GET {} HTTP/1.0\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: advent-of-rust-2021\r\n\r\n",
+            path, host, self.session
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| InputError::InvalidResponse("Missing end of headers".to_owned()))?;
+        let status = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(|| {
+                InputError::InvalidResponse(format!("Invalid status line in {}", head))
+            })?;
+
+        match status {
+            200 => Ok(body.to_owned()),
+            status => Err(InputError::Status {
+                status,
+                body: body.to_owned(),
+            }),
+        }
+    }
+
+    fn fetch_curl(&self, url: &str) -> Result<String, InputError> {
+        // The cookie is passed on stdin to keep the session out of the process list
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+            .args(["--max-time", &TIMEOUT.as_secs().to_string(), url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        curl.stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(format!("Cookie: session={}\n", self.session).as_bytes())?;
+
+        let output = curl.wait_with_output()?;
+        if !output.status.success() {
+            return Err(InputError::InvalidResponse(format!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        String::from_utf8(output.stdout)
+            .map_err(|_| InputError::InvalidResponse("Input isn't UTF-8".to_owned()))
+    }
+}
+
+impl fmt::Debug for HttpFetcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<HttpFetcher base_url={} >", self.base_url)
+    }
+}
+
+impl Fetch for HttpFetcher {
+    fn fetch(&self, day: u8) -> Result<String, InputError> {
+        // Line breaks would let the session add headers of its own
+        if self.session.contains(['\r', '\n']) {
+            return Err(InputError::InvalidSession);
+        }
+
+        let url = self.url(day);
+
+        if url.starts_with("https://") {
+            self.fetch_curl(&url)
+        } else {
+            self.fetch_plain(&url)
+        }
+    }
+}
+
+/// Provides the input for each day, from the cache when possible.
+pub struct Inputs {
+    cache_dir: PathBuf,
+    fetcher: Option<Box<dyn Fetch>>,
+}
+
+impl Inputs {
+    /// Inputs that are only ever read from `cache_dir`.
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            fetcher: None,
+        }
+    }
+
+    /// Fetch inputs that aren't cached with `fetcher`.
+    pub fn with_fetcher(mut self, fetcher: impl Fetch + 'static) -> Self {
+        self.fetcher = Some(Box::new(fetcher));
+        self
+    }
+
+    /// Configure inputs from the environment.
+    ///
+    /// * `AOC_CACHE_DIR`: the cache directory, defaults to the current directory.
+    /// * `AOC_SESSION`: the session cookie, nothing is fetched without it.
+    /// * `AOC_BASE_URL`: where to fetch from, defaults to [`HttpFetcher::DEFAULT_BASE_URL`].
+    pub fn from_env() -> Self {
+        let inputs = Self::new(env::var_os("AOC_CACHE_DIR").unwrap_or_else(|| ".".into()));
+
+        match env::var("AOC_SESSION") {
+            Ok(session) if !session.trim().is_empty() => {
+                let base_url = env::var("AOC_BASE_URL")
+                    .unwrap_or_else(|_| HttpFetcher::DEFAULT_BASE_URL.to_owned());
+
+                inputs.with_fetcher(HttpFetcher::new(base_url, session.trim()))
+            }
+            _ => inputs,
+        }
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Where the input for `day` is cached.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{:02}.txt", day))
+    }
+
+    /// The input for `day` if it's cached.
+    pub fn cached(&self, day: u8) -> Result<Option<String>, InputError> {
+        match fs::read_to_string(self.path(day)) {
+            Ok(input) => Ok(Some(input)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The input for `day`, fetching and caching it if needed.
+    pub fn get(&self, day: u8) -> Result<String, InputError> {
+        if let Some(input) = self.cached(day)? {
+            return Ok(input);
+        }
+
+        let fetcher = self
+            .fetcher
+            .as_ref()
+            .ok_or_else(|| InputError::NotCached(self.path(day)))?;
+        let input = fetcher.fetch(day)?;
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(self.path(day), &input)?;
+
+        Ok(input)
+    }
+}
+
+impl fmt::Debug for Inputs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Inputs cache_dir={} fetches={} >",
+            self.cache_dir.display(),
+            self.fetcher.is_some()
+        )
+    }
+}
+
+/// A local stand-in for the Advent of Code website, for testing fetching.
+#[cfg(test)]
+mod mock {
+    use std::collections::HashMap;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    use super::YEAR;
+
+    /// A request received by a [`MockServer`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Request {
+        pub path: String,
+        pub session: Option<String>,
+    }
+
+    /// A local stand-in for the Advent of Code website that serves a fixed set of inputs.
+    ///
+    /// Like the real thing it responds with `400` without a session cookie and `404` for days it
+    /// doesn't know about.
+    pub struct MockServer {
+        address: SocketAddr,
+        requests: Arc<Mutex<Vec<Request>>>,
+        stop: Arc<AtomicBool>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl MockServer {
+        pub fn start(inputs: HashMap<u8, String>) -> io::Result<Self> {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+            let address = listener.local_addr()?;
+            let requests = Arc::new(Mutex::new(vec![]));
+            let stop = Arc::new(AtomicBool::new(false));
+
+            let handle = {
+                let (requests, stop) = (Arc::clone(&requests), Arc::clone(&stop));
+
+                thread::spawn(move || {
+                    for stream in listener.incoming() {
+                        if stop.load(Ordering::SeqCst) {
+                            break;
+                        }
+
+                        if let Ok(stream) = stream {
+                            // A misbehaving client only affects its own request
+                            let _ = Self::respond(stream, &inputs, &requests);
+                        }
+                    }
+                })
+            };
+
+            Ok(Self {
+                address,
+                requests,
+                stop,
+                handle: Some(handle),
+            })
+        }
+
+        pub fn base_url(&self) -> String {
+            format!("http://{}", self.address)
+        }
+
+        /// Every request received so far, in order.
+        pub fn requests(&self) -> Vec<Request> {
+            self.requests
+                .lock()
+                .expect("Lock is never poisoned")
+                .clone()
+        }
+
+        fn respond(
+            mut stream: TcpStream,
+            inputs: &HashMap<u8, String>,
+            requests: &Mutex<Vec<Request>>,
+        ) -> io::Result<()> {
+            let mut reader = BufReader::new(stream.try_clone()?);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line)?;
+
+            let mut session = None;
+            loop {
+                let mut header = String::new();
+                if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                    break;
+                }
+
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("cookie") {
+                        session = value
+                            .split(';')
+                            .find_map(|c| c.trim().strip_prefix("session="))
+                            .filter(|s| !s.is_empty())
+                            .map(str::to_owned);
+                    }
+                }
+            }
+
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_owned();
+            let day = path
+                .strip_prefix(&format!("/{}/day/", YEAR))
+                .and_then(|rest| rest.strip_suffix("/input"))
+                .and_then(|day| day.parse::<u8>().ok());
+
+            let (status, body) = match (&session, day.and_then(|d| inputs.get(&d))) {
+                (None, _) => (
+                    "400 Bad Request",
+                    "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+                ),
+                (Some(_), None) => ("404 Not Found", "404 Not Found\n"),
+                (Some(_), Some(input)) => ("200 OK", input.as_str()),
+            };
+
+            requests
+                .lock()
+                .expect("Lock is never poisoned")
+                .push(Request { path, session });
+
+            write!(
+                stream,
+                "HTTP/1.0 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+        }
+    }
+
+    impl Drop for MockServer {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::SeqCst);
+            // Wake up the accept loop so it notices it should stop
+            let _ = TcpStream::connect(self.address);
+
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::mock::{MockServer, Request};
+    use super::{Fetch, HttpFetcher, InputError, Inputs};
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "advent-of-rust-2021-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    fn server() -> MockServer {
+        MockServer::start(HashMap::from([(
+            17,
+            "target area: x=20..30, y=-10..-5\n".to_owned(),
+        )]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let server = server();
+        let dir = cache_dir("fetch");
+        let inputs = Inputs::new(&dir).with_fetcher(HttpFetcher::new(server.base_url(), "abc"));
+
+        assert_eq!(inputs.cached(17).unwrap(), None);
+        assert_eq!(
+            inputs.get(17).unwrap(),
+            "target area: x=20..30, y=-10..-5\n"
+        );
+        assert_eq!(
+            inputs.get(17).unwrap(),
+            fs::read_to_string(dir.join("day17.txt")).unwrap()
+        );
+
+        // The second read comes from the cache
+        assert_eq!(
+            server.requests(),
+            vec![Request {
+                path: "/2021/day/17/input".to_owned(),
+                session: Some("abc".to_owned())
+            }]
+        );
+
+        assert!(matches!(
+            inputs.get(18),
+            Err(InputError::Status { status: 404, .. })
+        ));
+        assert!(!dir.join("day18.txt").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_only() {
+        let dir = cache_dir("cache-only");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "199\n200\n").unwrap();
+        let inputs = Inputs::new(&dir);
+
+        assert_eq!(inputs.get(1).unwrap(), "199\n200\n");
+        assert!(matches!(inputs.get(2), Err(InputError::NotCached(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_http_fetcher() {
+        let server = server();
+
+        let fetcher = HttpFetcher::new(format!("{}/", server.base_url()), "");
+        assert_eq!(
+            fetcher.url(17),
+            format!("{}/2021/day/17/input", server.base_url())
+        );
+        assert!(matches!(
+            fetcher.fetch(17),
+            Err(InputError::Status { status: 400, .. })
+        ));
+        assert!(matches!(
+            HttpFetcher::new("ftp://localhost", "abc").fetch(1),
+            Err(InputError::UnsupportedUrl(_))
+        ));
+
+        let injected = HttpFetcher::new(server.base_url(), "abc\r\nX-Injected: 1");
+        assert!(matches!(
+            injected.fetch(17),
+            Err(InputError::InvalidSession)
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -1,94 +1,536 @@
 #![allow(dead_code)]
-#[allow(unused_variables)]
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
 use std::str::FromStr;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 #[allow(dead_code, unused_imports)]
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod math;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod answers;
+pub mod bench;
+pub mod examples;
+pub mod input;
+pub mod math;
+pub mod ocr;
+pub mod registration;
+pub mod search;
+
+/// The answer to one part of a day.
+///
+/// Solutions return whatever type is natural for the puzzle, this is the uniform representation
+/// used when running days generically.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! define_answer_from_number {
+    ($($T:ty),*) => {
+        $(
+            impl From<$T> for Answer {
+                fn from(n: $T) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+define_answer_from_number!(u32, u64, usize, i32, i64);
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// An error produced when solving a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A unit of the input could not be parsed.
+    Parse(ParseError),
+    /// Several units of the input could not be parsed.
+    ParseMany(ParseErrors),
+    /// The input is malformed in a way that isn't tied to a single parsed unit.
+    InvalidInput(String),
+    /// The input is well formed but doesn't have an answer.
+    NoSolution(String),
+}
+
+impl Error {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Error::InvalidInput(message.into())
+    }
+
+    pub fn no_solution(message: impl Into<String>) -> Self {
+        Error::NoSolution(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::ParseMany(e) => write!(f, "{}", e),
+            Error::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+            Error::NoSolution(message) => write!(f, "No solution: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<ParseErrors> for Error {
+    fn from(e: ParseErrors) -> Self {
+        Error::ParseMany(e)
+    }
+}
+
+/// A solution to both parts of a day.
+///
+/// The input is parsed once with [`Solution::parse`] and then shared between the two parts.
+pub trait Solution {
+    type Input;
+    type One: Into<Answer>;
+    type Two: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    fn star_one(input: &Self::Input) -> Result<Self::One, Error>;
+
+    fn star_two(input: &Self::Input) -> Result<Self::Two, Error>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Invalid part {}, expected 1 or 2", s)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A type erased [`Solution`] as found in [`DAYS`].
+pub struct Day {
+    pub number: u8,
+    star_one: fn(&str) -> Result<Answer, Error>,
+    star_two: fn(&str) -> Result<Answer, Error>,
+    bench: fn(u8, &str, &bench::Config) -> Result<bench::DayBench, Error>,
+}
+
+impl Day {
+    const fn new<S: Solution>(number: u8) -> Self {
+        Self {
+            number,
+            star_one: solve_star_one::<S>,
+            star_two: solve_star_two::<S>,
+            bench: bench::bench_solution::<S>,
+        }
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<Answer, Error> {
+        match part {
+            Part::One => (self.star_one)(input),
+            Part::Two => (self.star_two)(input),
+        }
+    }
 
+    /// Time parsing `input` and solving both parts, see [`bench::Config`].
+    pub fn bench(&self, input: &str, config: &bench::Config) -> Result<bench::DayBench, Error> {
+        (self.bench)(self.number, input, config)
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Day number={} >", self.number)
+    }
+}
+
+fn solve_star_one<S: Solution>(input: &str) -> Result<Answer, Error> {
+    S::star_one(&S::parse(input)?).map(Into::into)
+}
+
+fn solve_star_two<S: Solution>(input: &str) -> Result<Answer, Error> {
+    S::star_two(&S::parse(input)?).map(Into::into)
+}
+
+/// Every day, in order.
+pub static DAYS: [Day; 24] = [
+    Day::new::<day01::Day01>(1),
+    Day::new::<day02::Day02>(2),
+    Day::new::<day03::Day03>(3),
+    Day::new::<day04::Day04>(4),
+    Day::new::<day05::Day05>(5),
+    Day::new::<day06::Day06>(6),
+    Day::new::<day07::Day07>(7),
+    Day::new::<day08::Day08>(8),
+    Day::new::<day09::Day09>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day19::Day19>(19),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+    Day::new::<day22::Day22>(22),
+    Day::new::<day23::Day23>(23),
+    Day::new::<day24::Day24>(24),
+];
+
+/// Find a day in [`DAYS`] by its number.
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+/// Unsigned integer types that can be split into digits, see [`DigitIterator`].
+pub trait Unsigned: Copy + Ord + fmt::Debug {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn div(self, rhs: Self) -> Self;
+    fn rem(self, rhs: Self) -> Self;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! define_unsigned {
+    ($($T:ty),*) => {
+        $(
+            impl Unsigned for $T {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn div(self, rhs: Self) -> Self {
+                    self / rhs
+                }
+
+                fn rem(self, rhs: Self) -> Self {
+                    self % rhs
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$T>::checked_mul(self, rhs)
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$T>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+define_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Iterator over the digits of a number, least significant first.
+///
+/// Use [`Iterator::rev`] for the most significant digit first. Zero has a single digit.
 #[derive(Debug, Copy, Clone)]
-pub struct DigitIterator {
-    initial_value_is_zero: bool,
-    number: f64,
+pub struct DigitIterator<T> {
+    /// The digits not yet yielded from either end.
+    number: T,
+    radix: T,
+    /// `radix` to the power of the number of remaining digits minus one.
+    divisor: T,
+    len: usize,
 }
 
-impl DigitIterator {
-    fn new(number: usize) -> Self {
+impl<T: Unsigned> DigitIterator<T> {
+    /// The decimal digits of `number`.
+    pub fn new(number: T) -> Self
+    where
+        T: From<u8>,
+    {
+        Self::with_radix(number, T::from(10))
+    }
+
+    /// The digits of `number` in base `radix`, which must be at least 2.
+    pub fn with_radix(number: T, radix: T) -> Self {
+        assert!(radix > T::ONE, "Radix must be at least 2, got {:?}", radix);
+
+        let (mut divisor, mut len) = (T::ONE, 1);
+        // Dividing rather than multiplying the divisor avoids overflow for large numbers
+        let mut rest = number.div(radix);
+        while rest > T::ZERO {
+            divisor = divisor.checked_mul(radix).expect("Divisor is at most number");
+            rest = rest.div(radix);
+            len += 1;
+        }
+
         Self {
-            initial_value_is_zero: number == 0,
-            number: number as f64,
+            number,
+            radix,
+            divisor,
+            len,
         }
     }
+
+    /// The number with the given digits in base `radix`, most significant first.
+    ///
+    /// Returns `None` if any digit isn't below `radix` or the number doesn't fit in `T`.
+    pub fn from_digits(digits: impl IntoIterator<Item = T>, radix: T) -> Option<T> {
+        digits.into_iter().try_fold(T::ZERO, |acc, digit| {
+            if digit >= radix {
+                return None;
+            }
+
+            acc.checked_mul(radix)?.checked_add(digit)
+        })
+    }
 }
 
-impl Iterator for DigitIterator {
-    type Item = usize;
+impl<T: Unsigned> Iterator for DigitIterator<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.number < 1.0 && !self.initial_value_is_zero {
+        if self.len == 0 {
+            return None;
+        }
+
+        let digit = self.number.rem(self.radix);
+        self.number = self.number.div(self.radix);
+        self.divisor = self.divisor.div(self.radix);
+        self.len -= 1;
+
+        Some(digit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: Unsigned> DoubleEndedIterator for DigitIterator<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
             return None;
         }
 
-        if self.initial_value_is_zero {
-            self.initial_value_is_zero = false;
+        let digit = self.number.div(self.divisor);
+        self.number = self.number.rem(self.divisor);
+        self.divisor = self.divisor.div(self.radix);
+        self.len -= 1;
+
+        Some(digit)
+    }
+}
+
+impl<T: Unsigned> ExactSizeIterator for DigitIterator<T> {}
+
+/// An error produced by the `try_parse_*` family of functions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the offending text, starting at 1.
+    pub line: usize,
+    /// The column, in characters, where the offending text starts, starting at 1.
+    pub column: usize,
+    /// The index of the offending text among all parsed units, starting at 0.
+    pub index: usize,
+    /// The offending text.
+    pub text: String,
+    /// The name of the type the text was parsed as.
+    pub type_name: &'static str,
+    /// The error returned by the type's [`FromStr`] implementation.
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse `{}` as `{}` at line {}, column {} (unit {}): {}",
+            self.text, self.type_name, self.line, self.column, self.index, self.reason
+        )
+    }
+}
 
-            Some(0)
-        } else {
-            let digit = self.number % 10_f64;
-            self.number = (self.number / 10_f64).floor();
+impl std::error::Error for ParseError {}
 
-            Some(digit as usize)
+/// All errors encountered when parsing, see [`collect_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, error) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+/// Collect the results of one of the `try_parse_*` functions, keeping every error rather than
+/// stopping at the first one like collecting into a `Result<Vec<_>, _>` does.
+pub fn collect_all<T>(
+    results: impl IntoIterator<Item = Result<T, ParseError>>,
+) -> Result<Vec<T>, ParseErrors> {
+    let mut values = vec![];
+    let mut errors = vec![];
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => errors.push(error),
         }
     }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(ParseErrors(errors))
+    }
 }
 
-fn time<F>(label: &str, closure: F)
+/// Parse the non-empty, trimmed, `units` of `input` as `T`.
+///
+/// Each unit must be a sub slice of `input`, this is used to locate errors.
+fn try_parse_units<'a, T>(
+    input: &'a str,
+    units: impl Iterator<Item = &'a str> + 'a,
+) -> impl Iterator<Item = Result<T, ParseError>> + 'a
 where
-    F: Fn(),
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
 {
-    let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-    closure();
-    let end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-    let time = end - start;
-    println!(
-        "Time taken for {}: {}s and {}ns",
-        label,
-        time.as_secs(),
-        time.subsec_nanos()
-    );
+    units
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .enumerate()
+        .map(move |(index, unit)| {
+            unit.parse().map_err(|e: <T as FromStr>::Err| {
+                let offset = unit.as_ptr() as usize - input.as_ptr() as usize;
+                let before = &input[..offset];
+
+                ParseError {
+                    line: before.matches('\n').count() + 1,
+                    column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+                    index,
+                    text: unit.to_owned(),
+                    type_name: std::any::type_name::<T>(),
+                    reason: e.to_string(),
+                }
+            })
+        })
+}
+
+/// Parse lines of text into custom types.
+///
+/// Each line is treated as parsable after trimming, empty lines are skipped.
+pub fn try_parse_lines<T>(input: &str) -> impl Iterator<Item = Result<T, ParseError>> + '_
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    try_parse_units(input, input.lines())
+}
+
+/// Parse whitespace separated custom types.
+///
+/// Each unit separated by whitespace is treated as parsable.
+pub fn try_parse_whitespace_separated<T>(
+    input: &str,
+) -> impl Iterator<Item = Result<T, ParseError>> + '_
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    try_parse_units(input, input.split_whitespace())
+}
+
+/// Parse custom separator separated custom types.
+///
+/// Each unit separated by a specific separator is treated as parsable after trimming.
+pub fn try_parse_custom_separated<'a, T>(
+    input: &'a str,
+    separator: &'a str,
+) -> impl Iterator<Item = Result<T, ParseError>> + 'a
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    try_parse_units(input, input.split(separator))
 }
 
 /// Parse lines of text into custom types.
 ///
 /// Each line is treated as parsable after trimming.
 ///
-/// **Note:** Panics if any parsing fails
+/// **Note:** Panics if any parsing fails, see [`try_parse_lines`] for a fallible version.
 pub fn parse_lines<T>(input: &str) -> impl Iterator<Item = T> + '_
 where
     T: FromStr + std::fmt::Debug,
@@ -97,13 +539,15 @@ where
     input
         .lines()
         .map(str::trim)
-        .filter(|l| l.len() > 0)
+        .filter(|l| !l.is_empty())
         .map(|l| {
-            l.parse().expect(&format!(
-                "Expected to be able to parse `{:?}` as `{:?}`",
-                l,
-                std::any::type_name::<T>()
-            ))
+            l.parse().unwrap_or_else(|_| {
+                panic!(
+                    "Expected to be able to parse `{:?}` as `{:?}`",
+                    l,
+                    std::any::type_name::<T>()
+                )
+            })
         })
 }
 
@@ -111,7 +555,8 @@ where
 ///
 /// Each unit separated by whitespace is treated as parsable after trimming.
 ///
-/// **Note:** Panics if any parsing fails
+/// **Note:** Panics if any parsing fails, see [`try_parse_whitespace_separated`] for a fallible
+/// version.
 pub fn parse_whitespace_separated<T>(input: &str) -> impl Iterator<Item = T> + '_
 where
     T: FromStr + std::fmt::Debug,
@@ -120,13 +565,15 @@ where
     input
         .split_whitespace()
         .map(str::trim)
-        .filter(|l| l.len() > 0)
+        .filter(|l| !l.is_empty())
         .map(|l| {
-            l.parse().expect(&format!(
-                "Expected to be able to parse `{:?}` as `{:?}`",
-                l,
-                std::any::type_name::<T>()
-            ))
+            l.parse().unwrap_or_else(|_| {
+                panic!(
+                    "Expected to be able to parse `{:?}` as `{:?}`",
+                    l,
+                    std::any::type_name::<T>()
+                )
+            })
         })
 }
 
@@ -134,7 +581,8 @@ where
 ///
 /// Each unit separated by a specific separator is treated as parsable after trimming.
 ///
-/// **Note:** Panics if any parsing fails
+/// **Note:** Panics if any parsing fails, see [`try_parse_custom_separated`] for a fallible
+/// version.
 pub fn parse_custom_separated<'a, T>(
     input: &'a str,
     separator: &'a str,
@@ -146,13 +594,15 @@ where
     input
         .split(separator)
         .map(str::trim)
-        .filter(|l| l.len() > 0)
+        .filter(|l| !l.is_empty())
         .map(|l| {
-            l.parse().expect(&format!(
-                "Expected to be able to parse `{:?}` as `{:?}`",
-                l,
-                std::any::type_name::<T>()
-            ))
+            l.parse().unwrap_or_else(|_| {
+                panic!(
+                    "Expected to be able to parse `{:?}` as `{:?}`",
+                    l,
+                    std::any::type_name::<T>()
+                )
+            })
         })
 }
 
@@ -166,254 +616,258 @@ pub fn load_file(path: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
-    use std::io::Read;
-
-    fn load_file(path: &str) -> String {
-        let mut input = String::new();
-        let mut f = File::open(path).expect("Unable to open file");
-        f.read_to_string(&mut input).expect("Unable to read string");
-
-        input
-    }
-
-    #[test]
-    fn solve_day01() {
-        use crate::day01::{star_one, star_two};
-
-        let input = load_file("day01.txt");
-
-        assert_eq!(star_one(&input), 1624);
-        assert_eq!(star_two(&input), 1653);
-    }
-
-    #[test]
-    fn solve_day02() {
-        use crate::day02::{star_one, star_two};
-
-        let input = load_file("day02.txt");
-
-        assert_eq!(star_one(&input), 2073315);
-        assert_eq!(star_two(&input), 1840311528);
-    }
-
-    #[test]
-    fn solve_day03() {
-        use crate::day03::{star_one, star_two};
-
-        let input = load_file("day03.txt");
-
-        assert_eq!(star_one(&input), 4147524);
-        assert_eq!(star_two(&input), 3570354);
-    }
-
-    #[test]
-    fn solve_day04() {
-        use crate::day04::{star_one, star_two};
-
-        let input = load_file("day04.txt");
-
-        assert_eq!(star_one(&input), 29440);
-        assert_eq!(star_two(&input), 13884);
-    }
-
-    #[test]
-    fn solve_day05() {
-        use crate::day05::{star_one, star_two};
-
-        let input = load_file("day05.txt");
-
-        super::time("Day 5, part 1", || assert_eq!(star_one(&input), 5169));
-        super::time("Day 5, part 2", || assert_eq!(star_two(&input), 22083));
-    }
-
-    #[test]
-    fn solve_day06() {
-        use crate::day06::{star_one, star_two};
-
-        let input = load_file("day06.txt");
-
-        super::time("Day 6, part 1", || assert_eq!(star_one(&input), 350605));
-        super::time("Day 6, part 2", || {
-            assert_eq!(star_two(&input), 1592778185024)
-        });
-    }
-
-    #[test]
-    fn solve_day07() {
-        use crate::day07::{star_one, star_two};
-
-        let input = load_file("day07.txt");
+    use crate::input::Inputs;
 
-        assert_eq!(star_one(&input), 356958);
-        assert_eq!(star_two(&input), 105461913);
+    /// The cached input for `day`, never fetched so the tests work offline.
+    fn load_input(day: u8) -> String {
+        Inputs::new(env!("CARGO_MANIFEST_DIR"))
+            .get(day)
+            .unwrap_or_else(|e| panic!("Unable to load input for day {}: {}", day, e))
     }
 
     #[test]
-    fn solve_day08() {
-        use crate::day08::{star_one, star_two};
+    fn test_days_registry() {
+        use crate::{find_day, Answer, Part, DAYS};
 
-        let input = load_file("day08.txt");
-
-        assert_eq!(star_one(&input), 330);
-        assert_eq!(star_two(&input), 1010472);
-    }
-
-    #[test]
-    fn solve_day09() {
-        use crate::day09::{star_one, star_two};
-
-        let input = load_file("day09.txt");
-
-        assert_eq!(star_one(&input), 633);
-        assert_eq!(star_two(&input), 1050192);
-    }
-
-    #[test]
-    fn solve_day10() {
-        use crate::day10::{star_one, star_two};
-
-        let input = load_file("day10.txt");
-
-        assert_eq!(star_one(&input), 344193);
-        assert_eq!(star_two(&input), 3241238967);
-    }
-
-    #[test]
-    fn solve_day11() {
-        use crate::day11::{star_one, star_two};
-
-        let input = load_file("day11.txt");
-
-        assert_eq!(star_one(&input), 1749);
-        assert_eq!(star_two(&input), 285);
-    }
-
-    #[test]
-    fn solve_day12() {
-        use crate::day12::{star_one, star_two};
-
-        let input = load_file("day12.txt");
-
-        assert_eq!(star_one(&input), 3887);
-        assert_eq!(star_two(&input), 104834);
-    }
-
-    #[test]
-    fn solve_day13() {
-        use crate::day13::{star_one, star_two};
-
-        let input = load_file("day13.txt");
-
-        assert_eq!(star_one(&input), 724);
-        assert_eq!(star_two(&input), "CPJBERVL");
-    }
-
-    #[test]
-    fn solve_day14() {
-        use crate::day14::{star_one, star_two};
-
-        let input = load_file("day14.txt");
+        for (idx, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number as usize, idx + 1);
+        }
 
-        assert_eq!(star_one(&input), 3259);
-        assert_eq!(star_two(&input), 3459174981021);
+        let day = find_day(1).unwrap();
+        assert_eq!(
+            day.solve(Part::One, "199\n200\n208\n210"),
+            Ok(Answer::Number(3))
+        );
+        assert!(find_day(25).is_none());
     }
 
     #[test]
-    fn solve_day15() {
-        use crate::day15::{star_one, star_two};
-
-        let input = load_file("day15.txt");
-
-        assert_eq!(star_one(&input), 687);
-        assert_eq!(star_two(&input), 2957);
+    fn test_digit_iterator() {
+        use crate::DigitIterator;
+
+        assert_eq!(DigitIterator::new(1234_u32).collect::<Vec<_>>(), [4, 3, 2, 1]);
+        assert_eq!(DigitIterator::new(1234_u32).rev().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(DigitIterator::new(0_u8).collect::<Vec<_>>(), [0]);
+        assert_eq!(DigitIterator::new(u8::MAX).rev().collect::<Vec<_>>(), [2, 5, 5]);
+        assert_eq!(
+            DigitIterator::with_radix(0b1011_u16, 2).rev().collect::<Vec<_>>(),
+            [1, 0, 1, 1]
+        );
+        assert_eq!(
+            DigitIterator::with_radix(0xBEEF_usize, 16).collect::<Vec<_>>(),
+            [0xF, 0xE, 0xE, 0xB]
+        );
+
+        // Precision isn't lost beyond 2^53
+        let large = 9_007_199_254_740_993_u64;
+        assert_eq!(DigitIterator::new(large).next(), Some(3));
+        assert_eq!(DigitIterator::new(u128::MAX).len(), 39);
+
+        let mut digits = DigitIterator::new(12345_u64);
+        assert_eq!(digits.next(), Some(5));
+        assert_eq!(digits.next_back(), Some(1));
+        assert_eq!(digits.len(), 3);
+        assert_eq!(digits.next_back(), Some(2));
+        assert_eq!(digits.next(), Some(4));
+        assert_eq!(digits.next(), Some(3));
+        assert_eq!(digits.next_back(), None);
     }
 
     #[test]
-    fn solve_day16() {
-        use crate::day16::{star_one, star_two};
-
-        let input = load_file("day16.txt");
-
-        assert_eq!(star_one(&input), 901);
-        assert_eq!(star_two(&input), 110434737925);
+    fn test_from_digits() {
+        use crate::DigitIterator;
+
+        assert_eq!(DigitIterator::from_digits([1_u32, 2, 3, 4], 10), Some(1234));
+        assert_eq!(DigitIterator::from_digits([1_u8, 0, 1, 1], 2), Some(0b1011));
+        assert_eq!(DigitIterator::from_digits([], 10), Some(0_u64));
+        assert_eq!(DigitIterator::from_digits([2_u8, 5, 6], 10), None);
+        assert_eq!(DigitIterator::from_digits([1_u8, 10], 10), None);
+
+        for number in [0_u64, 7, 10, 987_654_321, u64::MAX] {
+            for radix in [2, 10, 16, 36] {
+                let digits = DigitIterator::with_radix(number, radix).rev();
+                assert_eq!(DigitIterator::from_digits(digits, radix), Some(number));
+            }
+        }
     }
 
     #[test]
-    fn solve_day17() {
-        use crate::day17::{star_one, star_two};
-
-        assert_eq!(star_one((94, -156), (151, -103)), 12090);
-        assert_eq!(star_two((94, -156), (151, -103)), 5059);
+    fn test_try_parse_lines() {
+        use crate::try_parse_lines;
+
+        let result: Result<Vec<u64>, _> = try_parse_lines("1\n 2\n\n3").collect();
+        assert_eq!(result, Ok(vec![1, 2, 3]));
+
+        let error = try_parse_lines::<u64>("1\n\n  x2\n3")
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.column, 3);
+        assert_eq!(error.index, 1);
+        assert_eq!(error.text, "x2");
+        assert_eq!(error.type_name, "u64");
     }
 
     #[test]
-    fn solve_day18() {
-        use crate::day18::{star_one, star_two};
-
-        let input = load_file("day18.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    fn test_try_parse_separated_collect_all() {
+        use crate::{collect_all, try_parse_custom_separated, try_parse_whitespace_separated};
+
+        let errors = collect_all(try_parse_custom_separated::<i64>("1,a,3,\n-4,b", ","))
+            .unwrap_err()
+            .0;
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            (errors[0].line, errors[0].column, errors[0].index),
+            (1, 3, 1)
+        );
+        assert_eq!(
+            (errors[1].line, errors[1].column, errors[1].index),
+            (2, 4, 4)
+        );
+        assert_eq!(errors[1].text, "b");
+
+        let values = collect_all(try_parse_whitespace_separated::<i64>("1 2\n  3"));
+        assert_eq!(values, Ok(vec![1, 2, 3]));
     }
 
-    #[test]
-    fn solve_day19() {
-        use crate::day19::{star_one, star_two};
-
-        let input = load_file("day19.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    /// Check both parts of each day against [`answers::ANSWERS_FILE`]. Days without a cached input
+    /// are marked `#[ignore]` and fail when run anyway.
+    macro_rules! solve_days {
+        ($($(#[$attr:meta])* $name:ident: $day:expr),* $(,)?) => {
+            $(
+                #[test]
+                $(#[$attr])*
+                fn $name() {
+                    check_recorded($day);
+                }
+            )*
+        };
     }
 
-    #[test]
-    fn solve_day20() {
-        use crate::day20::{star_one, star_two};
-
-        let input = load_file("day20.txt");
+    fn check_recorded(number: u8) {
+        use crate::{answers, find_day, Part};
 
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+        let inputs = Inputs::new(env!("CARGO_MANIFEST_DIR"));
+        let input = inputs.cached(number).unwrap().unwrap_or_else(|| {
+            panic!(
+                "Day {} has no input, {} doesn't exist",
+                number,
+                inputs.path(number).display()
+            )
+        });
+        let answers = answers::Answers::load(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(answers::ANSWERS_FILE),
+        )
+        .unwrap();
+        let day = find_day(number).unwrap();
+
+        for part in [Part::One, Part::Two] {
+            let outcome = answers::check(day, part, &input, &answers);
+
+            assert!(
+                matches!(outcome, answers::Outcome::Pass(_)),
+                "Day {} part {}: {}",
+                number,
+                part,
+                outcome
+            );
+        }
     }
 
-    #[test]
-    fn solve_day21() {
-        use crate::day21::{star_one, star_two};
-
-        let input = load_file("day21.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    solve_days! {
+        solve_day01: 1,
+        solve_day02: 2,
+        solve_day03: 3,
+        solve_day04: 4,
+        solve_day05: 5,
+        solve_day06: 6,
+        solve_day07: 7,
+        solve_day08: 8,
+        solve_day09: 9,
+        solve_day10: 10,
+        solve_day11: 11,
+        solve_day12: 12,
+        solve_day13: 13,
+        solve_day14: 14,
+        solve_day15: 15,
+        solve_day16: 16,
+        solve_day17: 17,
+        #[ignore = "no input"]
+        solve_day18: 18,
+        #[ignore = "no input"]
+        solve_day19: 19,
+        #[ignore = "no input"]
+        solve_day20: 20,
+        #[ignore = "no input"]
+        solve_day21: 21,
+        #[ignore = "no input"]
+        solve_day22: 22,
+        #[ignore = "no input"]
+        solve_day23: 23,
+        #[ignore = "no input"]
+        solve_day24: 24,
     }
 
-    #[test]
-    fn solve_day22() {
-        use crate::day22::{star_one, star_two};
-
-        let input = load_file("day22.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    /// Check every example in [`examples::EXAMPLES_DIR`] for each day.
+    macro_rules! example_days {
+        ($($name:ident: $day:expr),* $(,)?) => {
+            $(
+                #[test]
+                fn $name() {
+                    check_examples($day);
+                }
+            )*
+        };
     }
 
-    #[test]
-    fn solve_day23() {
-        use crate::day23::{star_one, star_two};
-
-        let input = load_file("day23.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    fn check_examples(number: u8) {
+        use crate::{examples, find_day};
+
+        let examples = examples::load(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(examples::EXAMPLES_DIR),
+            number,
+        )
+        .unwrap();
+        let day = find_day(number).unwrap();
+
+        let failures: Vec<_> = examples
+            .iter()
+            .filter_map(|example| example.check(day).err())
+            .flatten()
+            .collect();
+
+        assert!(
+            failures.is_empty(),
+            "Day {} examples failed:\n{}",
+            number,
+            failures.join("\n")
+        );
     }
 
-    #[test]
-    fn solve_day24() {
-        use crate::day24::{star_one, star_two};
-
-        let input = load_file("day24.txt");
-
-        assert_eq!(star_one(&input), 1);
-        assert_eq!(star_two(&input), 1);
+    example_days! {
+        examples_day01: 1,
+        examples_day02: 2,
+        examples_day03: 3,
+        examples_day04: 4,
+        examples_day05: 5,
+        examples_day06: 6,
+        examples_day07: 7,
+        examples_day08: 8,
+        examples_day09: 9,
+        examples_day10: 10,
+        examples_day11: 11,
+        examples_day12: 12,
+        examples_day13: 13,
+        examples_day14: 14,
+        examples_day15: 15,
+        examples_day16: 16,
+        examples_day17: 17,
+        examples_day18: 18,
+        examples_day19: 19,
+        examples_day20: 20,
+        examples_day21: 21,
+        examples_day22: 22,
+        examples_day23: 23,
+        examples_day24: 24,
     }
 }
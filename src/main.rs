@@ -0,0 +1,220 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+use advent_of_rust_2021::{
+    answers::{self, Answers, Outcome},
+    bench::{self, Report},
+    find_day,
+    input::Inputs,
+    Part, DAYS,
+};
+
+const USAGE: &str = "Usage: advent-of-rust-2021 <day> <part> [input]
+       advent-of-rust-2021 check [--record]
+       advent-of-rust-2021 bench [--json] [--warmup <runs>] [--iterations <runs>] [day...]
+
+Runs the solution for part 1 or 2 of the given day and prints the answer.
+Reads the puzzle input from the given path, or stdin when the path is `-`. When the path is
+omitted the input is read from `AOC_CACHE_DIR` (default `.`) as `dayNN.txt`, and downloaded
+there first if `AOC_SESSION` is set. `AOC_BASE_URL` overrides where it's downloaded from.
+
+`check` solves every day with an input and compares the answers to those recorded in
+`answers.toml`. With `--record` answers that aren't recorded yet are added to it.
+
+`bench` times parsing and both parts of the given days, or every day with an input, and prints
+the min, median and mean of each as a table or as JSON.";
+
+fn read_input(day: u8, path: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    match path {
+        None => Ok(Inputs::from_env().get(day)?),
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+
+            Ok(input)
+        }
+        Some(path) => Ok(fs::read_to_string(path)?),
+    }
+}
+
+/// Check every day against the recorded answers, returning whether they all passed.
+fn check(record: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    let inputs = Inputs::from_env();
+    let mut answers = Answers::load(answers::ANSWERS_FILE)?;
+    let (mut passed, mut failed, mut missing, mut recorded) = (0, 0, 0, 0);
+
+    for day in DAYS.iter() {
+        let input = match inputs.get(day.number) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Day {:02}: no input, {}", day.number, e);
+                missing += 2;
+                continue;
+            }
+        };
+
+        for part in [Part::One, Part::Two] {
+            let outcome = answers::check(day, part, &input, &answers);
+            println!("Day {:02} part {}: {}", day.number, part, outcome);
+
+            match outcome {
+                Outcome::Pass(_) => passed += 1,
+                Outcome::Fail { .. } | Outcome::Error(_) => failed += 1,
+                Outcome::Missing(answer) if record => {
+                    answers.insert(day.number, part, answer);
+                    recorded += 1;
+                }
+                Outcome::Missing(_) => missing += 1,
+            }
+        }
+    }
+
+    if recorded > 0 {
+        answers.save(answers::ANSWERS_FILE)?;
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} missing, {} recorded",
+        passed, failed, missing, recorded
+    );
+
+    Ok(failed == 0)
+}
+
+/// Benchmark the days selected by `args`, returning whether they could all be benchmarked.
+fn bench(args: &[String]) -> Result<bool, String> {
+    let mut config = bench::Config::default();
+    let mut json = false;
+    let mut days = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut runs = || {
+            args.next()
+                .and_then(|runs| runs.parse().ok())
+                .ok_or_else(|| format!("Expected a number of runs after {}", arg))
+        };
+
+        match arg.as_str() {
+            "--json" => json = true,
+            "--warmup" => config.warmup = runs()?,
+            "--iterations" => config.iterations = runs()?,
+            day => days.push(
+                day.parse()
+                    .ok()
+                    .and_then(find_day)
+                    .ok_or_else(|| format!("Invalid day {}", day))?,
+            ),
+        }
+    }
+
+    let explicit = !days.is_empty();
+    if !explicit {
+        days = DAYS.iter().collect();
+    }
+
+    let inputs = Inputs::from_env();
+    let mut report = Report::default();
+    let mut all_ok = true;
+
+    for day in days {
+        let input = match inputs.get(day.number) {
+            Ok(input) => input,
+            // Only complain about missing inputs for days that were asked for
+            Err(e) if explicit => {
+                eprintln!("Skipping day {}: {}", day.number, e);
+                all_ok = false;
+                continue;
+            }
+            Err(_) => continue,
+        };
+
+        match day.bench(&input, &config) {
+            Ok(result) => report.days.push(result),
+            Err(e) => {
+                eprintln!("Failed to benchmark day {}: {}", day.number, e);
+                all_ok = false;
+            }
+        }
+    }
+
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        println!("{}", report);
+    }
+
+    Ok(all_ok)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("bench") {
+        match bench(&args[1..]) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                process::exit(1);
+            }
+        }
+    }
+
+    if args.first().map(String::as_str) == Some("check") {
+        let record = match args.get(1).map(String::as_str) {
+            None => false,
+            Some("--record") if args.len() == 2 => true,
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(1);
+            }
+        };
+
+        match check(record) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("Failed to check answers: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let (day, part) = match (
+        args.first().map(|d| d.parse::<u8>()),
+        args.get(1).map(|p| p.parse::<Part>()),
+    ) {
+        (Some(Ok(day)), Some(Ok(part))) => (day, part),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+
+    let day = match find_day(day) {
+        Some(day) => day,
+        None => {
+            eprintln!("Invalid day {}, expected 1 through 24", day);
+            process::exit(1);
+        }
+    };
+
+    let input = match read_input(day.number, args.get(2).map(String::as_str)) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Failed to read input: {}", e);
+            process::exit(1);
+        }
+    };
+
+    match day.solve(part, &input) {
+        Ok(answer) => println!("{}", answer),
+        Err(e) => {
+            eprintln!("Failed to solve day {} part {}: {}", day.number, part, e);
+            process::exit(1);
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt;
 use std::hash::Hash;
-use std::ops::{Add, Neg, Sub};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::Error;
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 pub struct Vector2<T> {
@@ -62,12 +65,95 @@ impl<T: Neg<Output = T> + Hash + PartialEq> Neg for Vector2<T> {
     }
 }
 
+impl<T: Mul<Output = T>> Mul for Vector2<T> {
+    type Output = Vector2<T>;
+
+    /// Component-wise multiplication.
+    fn mul(self, rhs: Vector2<T>) -> Self::Output {
+        Vector2::<T> {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+        }
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vector2::<T> {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vector2<T> {
+    fn add_assign(&mut self, rhs: Vector2<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vector2<T> {
+    fn sub_assign(&mut self, rhs: Vector2<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T> From<(T, T)> for Vector2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Vector2<T>> for (T, T) {
+    fn from(vector: Vector2<T>) -> Self {
+        (vector.x, vector.y)
+    }
+}
+
+impl<T: FromStr> FromStr for Vector2<T>
+where
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Err = Error;
+
+    /// Parse a vector from `x,y`, e.g. `3,-4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| Error::invalid_input(format!("Invalid vector {}", s)))?;
+
+        fn parse<V: FromStr>(value: &str, s: &str) -> Result<V, Error>
+        where
+            <V as FromStr>::Err: fmt::Display,
+        {
+            value.trim().parse().map_err(|e| {
+                Error::invalid_input(format!("Failed to parse vector {}, with error: {}", s, e))
+            })
+        }
+
+        Ok(Self {
+            x: parse(x, s)?,
+            y: parse(y, s)?,
+        })
+    }
+}
+
 pub trait Abs {
     type Output;
 
     fn abs(self) -> Self::Output;
 }
 
+pub trait Signum {
+    type Output;
+
+    fn signum(self) -> Self::Output;
+}
+
 macro_rules! define_abs {
     ($T:ident) => {
         impl Abs for $T {
@@ -80,10 +166,969 @@ macro_rules! define_abs {
     };
 }
 
+macro_rules! define_signum {
+    ($T:ident) => {
+        impl Signum for $T {
+            type Output = $T;
+
+            fn signum(self) -> Self::Output {
+                self.signum()
+            }
+        }
+    };
+}
+
+define_abs!(i32);
+define_abs!(i64);
 define_abs!(isize);
+define_abs!(f64);
+
+define_signum!(i32);
+define_signum!(i64);
+define_signum!(isize);
+define_signum!(f64);
 
 impl<T: Abs<Output = T> + Sub<Output = T> + Add<Output = T>> Vector2<T> {
     pub fn manhattan_distance(self, other: Self) -> T {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
 }
+
+impl<T: Abs<Output = T> + Sub<Output = T> + PartialOrd> Vector2<T> {
+    /// The number of king moves between `self` and `other`.
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+}
+
+impl<T: Signum<Output = T>> Vector2<T> {
+    /// The sign of each component, e.g. `<3, -2>` becomes `<1, -1>`.
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+impl Vector2<f64> {
+    pub fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Scale the vector to length one, the zero vector has no direction and yields `NaN`s.
+    pub fn normalize(self) -> Self {
+        self * (1.0 / self.length())
+    }
+}
+
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Copy> Vector3<T> {
+    fn axis(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => unreachable!("Vector3 only has three axes"),
+        }
+    }
+}
+
+impl<T: Default> Default for Vector3<T> {
+    fn default() -> Self {
+        Self {
+            x: T::default(),
+            y: T::default(),
+            z: T::default(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Vector3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Vector3 x={:?} y={:?} z={:?} >", self.x, self.y, self.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn add(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::<T> {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn sub(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::<T> {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn neg(self) -> Self::Output {
+        Vector3::<T> {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<T: Mul<Output = T>> Mul for Vector3<T> {
+    type Output = Vector3<T>;
+
+    /// Component-wise multiplication.
+    fn mul(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::<T> {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vector3::<T> {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vector3<T> {
+    fn add_assign(&mut self, rhs: Vector3<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vector3<T> {
+    fn sub_assign(&mut self, rhs: Vector3<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T> From<(T, T, T)> for Vector3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Vector3<T>> for (T, T, T) {
+    fn from(vector: Vector3<T>) -> Self {
+        (vector.x, vector.y, vector.z)
+    }
+}
+
+impl<T: FromStr> FromStr for Vector3<T>
+where
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Err = Error;
+
+    /// Parse a vector from `x,y,z`, e.g. `-618,-824,-621`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(',').map(|value| {
+            value.trim().parse().map_err(|e| {
+                Error::invalid_input(format!("Failed to parse vector {}, with error: {}", s, e))
+            })
+        });
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(x), Some(y), Some(z), None) => Ok(Self {
+                x: x?,
+                y: y?,
+                z: z?,
+            }),
+            _ => Err(Error::invalid_input(format!("Invalid vector {}", s))),
+        }
+    }
+}
+
+impl<T: Abs<Output = T> + Sub<Output = T> + Add<Output = T>> Vector3<T> {
+    pub fn manhattan_distance(self, other: Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+impl<T: Abs<Output = T> + Sub<Output = T> + PartialOrd> Vector3<T> {
+    /// The largest distance along any single axis between `self` and `other`.
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        let dz = (self.z - other.z).abs();
+
+        let dxy = if dx > dy { dx } else { dy };
+
+        if dxy > dz {
+            dxy
+        } else {
+            dz
+        }
+    }
+}
+
+impl<T: Signum<Output = T>> Vector3<T> {
+    /// The sign of each component, e.g. `<3, -2, 0>` becomes `<1, -1, 0>`.
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+            z: self.z.signum(),
+        }
+    }
+}
+
+impl Vector3<f64> {
+    pub fn length(self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    /// Scale the vector to length one, the zero vector has no direction and yields `NaN`s.
+    pub fn normalize(self) -> Self {
+        self * (1.0 / self.length())
+    }
+}
+
+/// Which cells count as neighbors of a cell in a [`Grid`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The four orthogonally adjacent cells.
+    Four,
+    /// The four orthogonally adjacent cells and the four diagonally adjacent cells.
+    Eight,
+}
+
+impl Neighborhood {
+    const FOUR: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    const EIGHT: [(isize, isize); 8] = [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (-1, -1),
+        (-1, 1),
+        (1, 1),
+        (1, -1),
+    ];
+
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Four => &Self::FOUR,
+            Neighborhood::Eight => &Self::EIGHT,
+        }
+    }
+}
+
+/// The locations of all cells in `neighborhood` of `location` within a `width` by `height` area
+/// with its origin in the top left corner.
+pub fn neighbors_within(
+    location: Vector2<usize>,
+    (width, height): (usize, usize),
+    neighborhood: Neighborhood,
+) -> impl Iterator<Item = Vector2<usize>> {
+    neighborhood.offsets().iter().filter_map(move |&(dx, dy)| {
+        let neighbor = Vector2::new(
+            location.x.checked_add_signed(dx)?,
+            location.y.checked_add_signed(dy)?,
+        );
+
+        (neighbor.x < width && neighbor.y < height).then_some(neighbor)
+    })
+}
+
+/// A rectangular grid of cells stored row by row, indexed by `Vector2<usize>` with the origin in
+/// the top left corner.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from rows of cells, all rows must have the same length.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self, Error> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for row in rows {
+            match width {
+                None => width = Some(row.len()),
+                Some(w) if w != row.len() => {
+                    return Err(Error::invalid_input(format!(
+                        "Grid rows have different lengths, expected {} got {}",
+                        w,
+                        row.len()
+                    )));
+                }
+                Some(_) => {}
+            }
+
+            height += 1;
+            cells.extend(row);
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(Error::invalid_input("Empty grid")),
+        }
+    }
+
+    /// Create a grid by calling `f` with the location of each cell, either dimension may be zero.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut(Vector2<usize>) -> T,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Vector2::new(x, y)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse a grid with one cell per character, mapping each character with `f`.
+    ///
+    /// Lines are trimmed and empty lines skipped.
+    pub fn parse_with<F>(input: &str, mut f: F) -> Result<Self, Error>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                l.chars()
+                    .map(|c| {
+                        f(c).ok_or_else(|| {
+                            Error::invalid_input(format!("Invalid cell `{}` in `{}`", c, l))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> Vector2<usize> {
+        Vector2::new(self.width, self.height)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, location: Vector2<usize>) -> bool {
+        location.x < self.width && location.y < self.height
+    }
+
+    pub fn get(&self, location: Vector2<usize>) -> Option<&T> {
+        self.contains(location)
+            .then(|| &self.cells[self.offset(location)])
+    }
+
+    pub fn get_mut(&mut self, location: Vector2<usize>) -> Option<&mut T> {
+        if self.contains(location) {
+            let offset = self.offset(location);
+
+            Some(&mut self.cells[offset])
+        } else {
+            None
+        }
+    }
+
+    /// The locations of all cells in `neighborhood` of `location` that are within the grid.
+    pub fn neighbors(
+        &self,
+        location: Vector2<usize>,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = Vector2<usize>> {
+        neighbors_within(location, (self.width, self.height), neighborhood)
+    }
+
+    /// All locations in the grid, row by row.
+    pub fn locations(&self) -> impl Iterator<Item = Vector2<usize>> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Vector2::new(x, y)))
+    }
+
+    /// All cells with their locations, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vector2<usize>, &T)> {
+        self.locations().zip(self.cells.iter())
+    }
+
+    /// All cells, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// All rows, none when the grid has no columns.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // A zero width grid has no cells, so any non-zero chunk size yields nothing
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height && self.width > 0)
+            .then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let start = if x < self.width { x } else { self.cells.len() };
+
+        self.cells.iter().skip(start).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn offset(&self, location: Vector2<usize>) -> usize {
+        location.y * self.width + location.x
+    }
+}
+
+impl Grid<u8> {
+    /// Parse a grid of single digits, e.g. `"123\n456"`.
+    pub fn parse_digits(input: &str) -> Result<Self, Error> {
+        Self::parse_with(input, |c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+impl Grid<char> {
+    /// Parse a grid of characters, e.g. `"#.#\n..#"`.
+    pub fn parse_chars(input: &str) -> Result<Self, Error> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Index<Vector2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, location: Vector2<usize>) -> &Self::Output {
+        assert!(
+            self.contains(location),
+            "{:?} is outside grid of size {:?}",
+            location,
+            self.size()
+        );
+
+        &self.cells[self.offset(location)]
+    }
+}
+
+impl<T> IndexMut<Vector2<usize>> for Grid<T> {
+    fn index_mut(&mut self, location: Vector2<usize>) -> &mut Self::Output {
+        assert!(
+            self.contains(location),
+            "{:?} is outside grid of size {:?}",
+            location,
+            self.size()
+        );
+
+        let offset = self.offset(location);
+        &mut self.cells[offset]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Grid width={} height={} cells={:?} >",
+            self.width, self.height, self.cells
+        )
+    }
+}
+
+/// A rotation by a multiple of 90° around the coordinate axes, i.e. one of the 24 ways to orient
+/// a cube.
+///
+/// Each row of the rotation matrix has a single non-zero entry, so it is stored as the source
+/// axis and sign of every output axis.
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Rotation {
+    rows: [(usize, bool); 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        rows: [(0, false), (1, false), (2, false)],
+    };
+
+    /// All 24 axis-aligned rotations, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        PERMUTATIONS
+            .iter()
+            .flat_map(|axes| {
+                (0..8_u8).map(move |signs| Rotation {
+                    rows: [
+                        (axes[0], signs & 1 != 0),
+                        (axes[1], signs & 2 != 0),
+                        (axes[2], signs & 4 != 0),
+                    ],
+                })
+            })
+            .filter(|r| r.determinant() == 1)
+            .collect()
+    }
+
+    /// The rotation matrix, `matrix[row][column]`.
+    pub fn matrix(&self) -> [[i64; 3]; 3] {
+        let mut matrix = [[0; 3]; 3];
+
+        for (row, &(axis, negate)) in self.rows.iter().enumerate() {
+            matrix[row][axis] = if negate { -1 } else { 1 };
+        }
+
+        matrix
+    }
+
+    /// The rotation that undoes this one.
+    pub fn inverse(&self) -> Rotation {
+        let mut rows = self.rows;
+
+        for (row, &(axis, negate)) in self.rows.iter().enumerate() {
+            rows[axis] = (row, negate);
+        }
+
+        Rotation { rows }
+    }
+
+    /// The rotation that first applies `self` and then `other`.
+    pub fn then(&self, other: &Rotation) -> Rotation {
+        Rotation {
+            rows: other.rows.map(|(axis, negate)| {
+                let (inner_axis, inner_negate) = self.rows[axis];
+
+                (inner_axis, negate != inner_negate)
+            }),
+        }
+    }
+
+    pub fn apply<T: Neg<Output = T> + Copy>(&self, vector: Vector3<T>) -> Vector3<T> {
+        let [x, y, z] = self.rows.map(|(axis, negate)| {
+            let value = vector.axis(axis);
+
+            if negate {
+                -value
+            } else {
+                value
+            }
+        });
+
+        Vector3::new(x, y, z)
+    }
+
+    fn determinant(&self) -> i64 {
+        let m = self.matrix();
+
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
+
+impl fmt::Debug for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Rotation matrix={:?} >", self.matrix())
+    }
+}
+
+/// An axis-aligned box of integer cells, both `min` and `max` are inclusive.
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Cuboid {
+    min: Vector3<i64>,
+    max: Vector3<i64>,
+}
+
+impl Cuboid {
+    /// Create the cuboid spanning `min` to `max` inclusive, `None` if it would be empty.
+    pub const fn new(min: Vector3<i64>, max: Vector3<i64>) -> Option<Self> {
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn min(&self) -> Vector3<i64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Vector3<i64> {
+        self.max
+    }
+
+    /// The number of cells in the cuboid.
+    pub fn volume(&self) -> u64 {
+        let size = self.max - self.min + Vector3::new(1, 1, 1);
+
+        size.x as u64 * size.y as u64 * size.z as u64
+    }
+
+    pub fn contains(&self, point: Vector3<i64>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    /// Whether every cell of `other` is also in `self`.
+    pub fn contains_cuboid(&self, other: &Cuboid) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        Cuboid::new(
+            Vector3::new(
+                self.min.x.max(other.min.x),
+                self.min.y.max(other.min.y),
+                self.min.z.max(other.min.z),
+            ),
+            Vector3::new(
+                self.max.x.min(other.max.x),
+                self.max.y.min(other.max.y),
+                self.max.z.min(other.max.z),
+            ),
+        )
+    }
+
+    /// The cells of `self` that are not in `other`, as at most six disjoint cuboids.
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let overlap = match self.intersection(other) {
+            None => return vec![*self],
+            Some(overlap) => overlap,
+        };
+
+        let mut remaining = *self;
+        let mut result = Vec::with_capacity(6);
+
+        // Slice off the parts below and above the overlap one axis at a time, each slice narrows
+        // what is left until only the overlap remains.
+        for axis in 0..3 {
+            let (low, high) = (overlap.min.axis(axis), overlap.max.axis(axis));
+
+            if remaining.min.axis(axis) < low {
+                let mut below = remaining;
+                set_axis(&mut below.max, axis, low - 1);
+                set_axis(&mut remaining.min, axis, low);
+                result.push(below);
+            }
+
+            if remaining.max.axis(axis) > high {
+                let mut above = remaining;
+                set_axis(&mut above.min, axis, high + 1);
+                set_axis(&mut remaining.max, axis, high);
+                result.push(above);
+            }
+        }
+
+        result
+    }
+}
+
+fn set_axis(vector: &mut Vector3<i64>, axis: usize, value: i64) {
+    match axis {
+        0 => vector.x = value,
+        1 => vector.y = value,
+        2 => vector.z = value,
+        _ => unreachable!("Vector3 only has three axes"),
+    }
+}
+
+impl fmt::Debug for Cuboid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Cuboid min={:?} max={:?} >", self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{Cuboid, Grid, Neighborhood, Rotation, Vector2, Vector3};
+
+    #[test]
+    fn test_vector2_arithmetic() {
+        let mut v = Vector2::new(3_i64, -4);
+
+        assert_eq!(v * 2, Vector2::new(6, -8));
+        assert_eq!(v * Vector2::new(2, 3), Vector2::new(6, -12));
+        assert_eq!(v.signum(), Vector2::new(1, -1));
+
+        v += Vector2::new(1, 1);
+        assert_eq!(v, Vector2::new(4, -3));
+        v -= Vector2::new(4, -3);
+        assert_eq!(v, Vector2::default());
+    }
+
+    #[test]
+    fn test_vector2_distances() {
+        let a = Vector2::new(1_i64, 2);
+        let b = Vector2::new(-2, 7);
+
+        assert_eq!(a.manhattan_distance(b), 8);
+        assert_eq!(a.chebyshev_distance(b), 5);
+        assert_eq!(Vector2::new(3.0, 4.0).length(), 5.0);
+        assert_eq!(Vector2::new(0.0, -2.0).normalize(), Vector2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn test_vector2_conversions() {
+        assert_eq!("3, -4".parse::<Vector2<i64>>(), Ok(Vector2::new(3, -4)));
+        assert!("3".parse::<Vector2<i64>>().is_err());
+        assert!("3,x".parse::<Vector2<i64>>().is_err());
+
+        let v: Vector2<u8> = (1, 2).into();
+        assert_eq!(v, Vector2::new(1, 2));
+        assert_eq!(<(u8, u8)>::from(v), (1, 2));
+    }
+
+    const INPUT: &str = r#"123
+456"#;
+
+    #[test]
+    fn test_grid_parse_digits() {
+        let grid = Grid::parse_digits(INPUT).unwrap();
+
+        assert_eq!(grid.size(), Vector2::new(3, 2));
+        assert_eq!(grid[Vector2::new(2, 1)], 6);
+        assert_eq!(grid.get(Vector2::new(3, 0)), None);
+        assert_eq!(grid.to_string(), INPUT);
+
+        assert!(Grid::parse_digits("12\n3").is_err());
+        assert!(Grid::parse_digits("1x").is_err());
+        assert!(Grid::parse_digits("").is_err());
+    }
+
+    #[test]
+    fn test_grid_rows_and_columns() {
+        let grid = Grid::parse_digits(INPUT).unwrap();
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3], &[4, 5, 6]]
+        );
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.copied().sum::<u8>())
+                .collect::<Vec<_>>(),
+            vec![5, 7, 9]
+        );
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+
+        let empty = Grid::from_fn(0, 3, |_| 0u8);
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.row(0), None);
+        assert_eq!(empty.column(0).count(), 0);
+        assert_eq!(empty.to_string(), "");
+    }
+
+    #[test]
+    fn test_grid_neighbors() {
+        let grid = Grid::parse_digits(INPUT).unwrap();
+
+        let mut four: Vec<_> = grid
+            .neighbors(Vector2::new(0, 0), Neighborhood::Four)
+            .map(|p| grid[p])
+            .collect();
+        four.sort();
+        assert_eq!(four, vec![2, 4]);
+
+        let mut eight: Vec<_> = grid
+            .neighbors(Vector2::new(1, 1), Neighborhood::Eight)
+            .map(|p| grid[p])
+            .collect();
+        eight.sort();
+        assert_eq!(eight, vec![1, 2, 3, 4, 6]);
+    }
+
+    #[test]
+    fn test_vector3_arithmetic() {
+        let mut v = Vector3::new(1_i64, -2, 3);
+
+        assert_eq!(v + Vector3::new(1, 1, 1), Vector3::new(2, -1, 4));
+        assert_eq!(v - Vector3::new(1, 1, 1), Vector3::new(0, -3, 2));
+        assert_eq!(-v, Vector3::new(-1, 2, -3));
+        assert_eq!(v * 2, Vector3::new(2, -4, 6));
+        assert_eq!(v.signum(), Vector3::new(1, -1, 1));
+
+        v += Vector3::new(1, 2, 3);
+        assert_eq!(v, Vector3::new(2, 0, 6));
+        v -= Vector3::new(2, 0, 6);
+        assert_eq!(v, Vector3::default());
+
+        assert_eq!(
+            "-618,-824,-621".parse::<Vector3<i64>>(),
+            Ok(Vector3::new(-618, -824, -621))
+        );
+        assert!("1,2".parse::<Vector3<i64>>().is_err());
+        assert!("1,2,3,4".parse::<Vector3<i64>>().is_err());
+    }
+
+    #[test]
+    fn test_vector3_distances() {
+        let a = Vector3::new(1105_i64, -1205, 1229);
+        let b = Vector3::new(-92, -2380, -20);
+
+        assert_eq!(a.manhattan_distance(b), 3621);
+        assert_eq!(a.chebyshev_distance(b), 1249);
+        assert_eq!(Vector3::new(2.0, 3.0, 6.0).length(), 7.0);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = Rotation::all();
+        let v = Vector3::new(1_i64, 2, 3);
+
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+
+        let rotated: HashSet<_> = rotations.iter().map(|r| r.apply(v)).collect();
+        assert_eq!(rotated.len(), 24);
+
+        for r in &rotations {
+            assert_eq!(r.inverse().apply(r.apply(v)), v);
+            assert_eq!(r.then(&r.inverse()), Rotation::IDENTITY);
+
+            for other in &rotations {
+                assert_eq!(r.then(other).apply(v), other.apply(r.apply(v)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let a = Cuboid::new(Vector3::new(10, 10, 10), Vector3::new(12, 12, 12)).unwrap();
+        let b = Cuboid::new(Vector3::new(11, 11, 11), Vector3::new(13, 13, 13)).unwrap();
+        let far = Cuboid::new(Vector3::new(20, 20, 20), Vector3::new(20, 20, 20)).unwrap();
+
+        assert!(Cuboid::new(Vector3::new(1, 0, 0), Vector3::new(0, 0, 0)).is_none());
+        assert_eq!(a.volume(), 27);
+        assert_eq!(far.volume(), 1);
+        assert!(a.contains(Vector3::new(12, 10, 11)));
+        assert!(!a.contains(Vector3::new(13, 10, 11)));
+
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(overlap.min(), Vector3::new(11, 11, 11));
+        assert_eq!(overlap.max(), Vector3::new(12, 12, 12));
+        assert!(a.intersection(&far).is_none());
+
+        let rest = a.subtract(&b);
+        assert_eq!(rest.iter().map(Cuboid::volume).sum::<u64>(), 27 - 8);
+        for (i, c) in rest.iter().enumerate() {
+            assert!(a.contains_cuboid(c));
+            assert!(c.intersection(&b).is_none());
+            assert!(rest[i + 1..].iter().all(|o| o.intersection(c).is_none()));
+        }
+
+        assert_eq!(a.subtract(&far), vec![a]);
+        assert!(a.subtract(&a).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! Recognition of the block letters some puzzles draw in dots.
+//!
+//! Letters are 4 pixels wide and 6 pixels tall, separated by a single column of unlit pixels.
+use crate::{
+    math::{Grid, Vector2},
+    Error,
+};
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+/// The number of unlit columns between two letters.
+pub const GLYPH_SPACING: usize = 1;
+
+const STRIDE: usize = GLYPH_WIDTH + GLYPH_SPACING;
+
+/// Every known letter, drawn with `#` for lit pixels.
+pub const ALPHABET: [(char, [&str; GLYPH_HEIGHT]); 16] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn glyph(letter: char) -> Option<&'static [&'static str; GLYPH_HEIGHT]> {
+    ALPHABET
+        .iter()
+        .find(|(c, _)| *c == letter)
+        .map(|(_, rows)| rows)
+}
+
+/// Read the letters drawn in `pixels`, starting at the top left corner.
+///
+/// Pixels missing to the right or bottom of the grid count as unlit, so the grid may be cropped
+/// to the lit pixels.
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, Error> {
+    if pixels.height() > GLYPH_HEIGHT {
+        return Err(Error::invalid_input(format!(
+            "Letters are {} pixels tall, found {} rows",
+            GLYPH_HEIGHT,
+            pixels.height()
+        )));
+    }
+
+    let lit = |x: usize, y: usize| pixels.get(Vector2::new(x, y)).copied().unwrap_or(false);
+    let letters = pixels.width().div_ceil(STRIDE);
+
+    (0..letters)
+        .map(|index| {
+            let left = index * STRIDE;
+
+            if (0..GLYPH_HEIGHT).any(|y| (GLYPH_WIDTH..STRIDE).any(|dx| lit(left + dx, y))) {
+                return Err(Error::invalid_input(format!(
+                    "Lit pixels between letters {} and {}",
+                    index,
+                    index + 1
+                )));
+            }
+
+            ALPHABET
+                .iter()
+                .find(|(_, rows)| {
+                    rows.iter().enumerate().all(|(y, row)| {
+                        row.chars()
+                            .enumerate()
+                            .all(|(dx, c)| (c == '#') == lit(left + dx, y))
+                    })
+                })
+                .map(|(c, _)| *c)
+                .ok_or_else(|| {
+                    let drawn: Vec<String> = (0..GLYPH_HEIGHT)
+                        .map(|y| {
+                            (0..GLYPH_WIDTH)
+                                .map(|dx| if lit(left + dx, y) { '#' } else { '.' })
+                                .collect()
+                        })
+                        .collect();
+
+                    Error::no_solution(format!(
+                        "Unknown letter {} drawn as {}",
+                        index,
+                        drawn.join("/")
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Draw `text` in the same block letters that [`recognize`] reads.
+pub fn render(text: &str) -> Result<Grid<bool>, Error> {
+    let glyphs = text
+        .chars()
+        .map(|c| glyph(c).ok_or_else(|| Error::invalid_input(format!("No glyph for `{}`", c))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let width = (glyphs.len() * STRIDE).saturating_sub(GLYPH_SPACING);
+
+    Ok(Grid::from_fn(width, GLYPH_HEIGHT, |p| {
+        let (index, dx) = (p.x / STRIDE, p.x % STRIDE);
+
+        dx < GLYPH_WIDTH && glyphs[index][p.y].as_bytes()[dx] == b'#'
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recognize, render, ALPHABET};
+    use crate::math::Grid;
+
+    #[test]
+    fn test_round_trip() {
+        let alphabet: String = ALPHABET.iter().map(|(c, _)| c).collect();
+
+        assert_eq!(recognize(&render(&alphabet).unwrap()), Ok(alphabet));
+        assert_eq!(recognize(&render("").unwrap()), Ok(String::new()));
+        assert!(render("V").is_err());
+    }
+
+    #[test]
+    fn test_recognize() {
+        let pixels = Grid::parse_with(
+            r#"#..#.####.#....#.....##.
+#..#.#....#....#....#..#
+####.###..#....#....#..#
+#..#.#....#....#....#..#
+#..#.#....#....#....#..#
+#..#.####.####.####..##."#,
+            |c| Some(c == '#'),
+        )
+        .unwrap();
+
+        assert_eq!(recognize(&pixels), Ok("HELLO".to_owned()));
+
+        // A trailing spacing column doesn't start another letter
+        let padded = Grid::from_fn(25, 6, |p| pixels.get(p).copied().unwrap_or(false));
+        assert_eq!(recognize(&padded), Ok("HELLO".to_owned()));
+
+        let unknown = Grid::from_fn(4, 6, |p| p.x == p.y);
+        assert!(recognize(&unknown).is_err());
+
+        let touching = Grid::from_fn(9, 6, |_| true);
+        assert!(recognize(&touching).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! Registration of 3D point clouds, i.e. finding the rigid transforms that bring several partially
+//! overlapping sets of points, each in its own frame of reference, into one shared frame.
+//!
+//! Only the 24 axis-aligned rotations are considered and points must match exactly.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::math::{Rotation, Vector3};
+use crate::Error;
+
+/// A rotation followed by a translation.
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Vector3<i64>,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: Vector3 { x: 0, y: 0, z: 0 },
+    };
+
+    pub fn apply(&self, point: Vector3<i64>) -> Vector3<i64> {
+        self.rotation.apply(point) + self.translation
+    }
+
+    /// The transform that first applies `self` and then `other`.
+    pub fn then(&self, other: &Transform) -> Transform {
+        Transform {
+            rotation: self.rotation.then(&other.rotation),
+            translation: other.apply(self.translation),
+        }
+    }
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Transform rotation={:?} translation={:?} >",
+            self.rotation, self.translation
+        )
+    }
+}
+
+/// The result of aligning several point clouds into the frame of the first one.
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    transforms: Vec<Transform>,
+    points: HashSet<Vector3<i64>>,
+}
+
+impl Alignment {
+    /// The transform from each cloud's frame into the shared frame, in the order the clouds were
+    /// given.
+    pub fn transforms(&self) -> &[Transform] {
+        &self.transforms
+    }
+
+    /// The origin of each cloud's frame in the shared frame, i.e. where the scanner was.
+    pub fn positions(&self) -> impl Iterator<Item = Vector3<i64>> + '_ {
+        self.transforms.iter().map(|t| t.translation)
+    }
+
+    /// All distinct points in the shared frame.
+    pub fn points(&self) -> &HashSet<Vector3<i64>> {
+        &self.points
+    }
+
+    /// The largest Manhattan distance between the origins of any two clouds.
+    pub fn max_manhattan_distance(&self) -> i64 {
+        self.positions()
+            .flat_map(|a| self.positions().map(move |b| a.manhattan_distance(b)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The squared distances between all pairs of points, these don't depend on the frame and are
+/// used to skip pairs of clouds that can't overlap enough.
+fn fingerprint(points: &[Vector3<i64>]) -> HashMap<i64, usize> {
+    let mut result = HashMap::new();
+
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            let d = *a - *b;
+            *result.entry(d.x * d.x + d.y * d.y + d.z * d.z).or_insert(0) += 1;
+        }
+    }
+
+    result
+}
+
+fn shared_distances(a: &HashMap<i64, usize>, b: &HashMap<i64, usize>) -> usize {
+    a.iter()
+        .map(|(distance, count)| b.get(distance).map_or(0, |other| *count.min(other)))
+        .sum()
+}
+
+/// Find the transform that maps at least `min_overlap` points of `other` onto points of
+/// `reference`.
+pub fn align_pair(
+    reference: &[Vector3<i64>],
+    other: &[Vector3<i64>],
+    min_overlap: usize,
+) -> Option<Transform> {
+    for rotation in Rotation::all() {
+        let rotated: Vec<_> = other.iter().map(|p| rotation.apply(*p)).collect();
+        let mut translations: HashMap<Vector3<i64>, usize> = HashMap::new();
+
+        for a in reference {
+            for b in &rotated {
+                let translation = *a - *b;
+                let count = translations.entry(translation).or_insert(0);
+                *count += 1;
+
+                if *count >= min_overlap {
+                    return Some(Transform {
+                        rotation,
+                        translation,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Align all `clouds` into the frame of the first one, each cloud must be connected to the first
+/// through a chain of pairs that share at least `min_overlap` points.
+pub fn align_all(clouds: &[Vec<Vector3<i64>>], min_overlap: usize) -> Result<Alignment, Error> {
+    if clouds.is_empty() {
+        return Err(Error::invalid_input("No point clouds to align"));
+    }
+
+    let fingerprints: Vec<_> = clouds.iter().map(|c| fingerprint(c)).collect();
+    let required_distances = min_overlap * min_overlap.saturating_sub(1) / 2;
+
+    let mut transforms: Vec<Option<Transform>> = vec![None; clouds.len()];
+    transforms[0] = Some(Transform::IDENTITY);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(reference) = queue.pop_front() {
+        let to_reference = transforms[reference].expect("Queued clouds are aligned");
+
+        for other in 0..clouds.len() {
+            if transforms[other].is_some()
+                || shared_distances(&fingerprints[reference], &fingerprints[other])
+                    < required_distances
+            {
+                continue;
+            }
+
+            if let Some(to_neighbor) = align_pair(&clouds[reference], &clouds[other], min_overlap) {
+                transforms[other] = Some(to_neighbor.then(&to_reference));
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let transforms = transforms
+        .into_iter()
+        .enumerate()
+        .map(|(i, t)| {
+            t.ok_or_else(|| {
+                Error::no_solution(format!(
+                    "Point cloud {} doesn't overlap with the others by {} points",
+                    i, min_overlap
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let points = clouds
+        .iter()
+        .zip(&transforms)
+        .flat_map(|(cloud, transform)| cloud.iter().map(move |p| transform.apply(*p)))
+        .collect();
+
+    Ok(Alignment { transforms, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align_all, align_pair, Transform};
+    use crate::math::{Rotation, Vector3};
+
+    fn cloud(seed: i64, count: i64) -> Vec<Vector3<i64>> {
+        (0..count)
+            .map(|i| {
+                // Scramble the bits so the points don't share structure that aligns by accident
+                let mut n = (seed * 1_000_003 + i) as u64;
+                let mut next = || {
+                    n ^= n << 13;
+                    n ^= n >> 7;
+                    n ^= n << 17;
+                    (n % 2001) as i64 - 1000
+                };
+
+                Vector3::new(next(), next(), next())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_transform_then() {
+        let rotations = Rotation::all();
+        let a = Transform {
+            rotation: rotations[5],
+            translation: Vector3::new(1, 2, 3),
+        };
+        let b = Transform {
+            rotation: rotations[17],
+            translation: Vector3::new(-40, 5, 60),
+        };
+        let p = Vector3::new(7, -8, 9);
+
+        assert_eq!(a.then(&b).apply(p), b.apply(a.apply(p)));
+        assert_eq!(Transform::IDENTITY.apply(p), p);
+    }
+
+    #[test]
+    fn test_align_pair() {
+        let reference = cloud(1, 20);
+        let transform = Transform {
+            rotation: Rotation::all()[11],
+            translation: Vector3::new(68, -1246, -43),
+        };
+        let inverse_rotation = transform.rotation.inverse();
+
+        // The other cloud sees the last 12 points of the reference plus some of its own
+        let mut other: Vec<_> = reference[8..]
+            .iter()
+            .map(|p| inverse_rotation.apply(*p - transform.translation))
+            .collect();
+        other.extend(cloud(2, 10));
+
+        assert_eq!(align_pair(&reference, &other, 12), Some(transform));
+        assert_eq!(align_pair(&reference, &other[1..], 12), None);
+    }
+
+    #[test]
+    fn test_align_all() {
+        let world = cloud(3, 40);
+        let rotations = Rotation::all();
+        let scanners = [
+            Transform::IDENTITY,
+            Transform {
+                rotation: rotations[3],
+                translation: Vector3::new(500, 20, -30),
+            },
+            Transform {
+                rotation: rotations[22],
+                translation: Vector3::new(-700, 1000, 80),
+            },
+        ];
+
+        // 0 and 2 only overlap through 1
+        let seen = [0..16, 12..28, 24..40];
+        let clouds: Vec<Vec<_>> = scanners
+            .iter()
+            .zip(seen)
+            .map(|(scanner, range)| {
+                let inverse = scanner.rotation.inverse();
+
+                world[range]
+                    .iter()
+                    .map(|p| inverse.apply(*p - scanner.translation))
+                    .collect()
+            })
+            .collect();
+
+        let alignment = align_all(&clouds, 4).unwrap();
+
+        assert_eq!(alignment.transforms(), &scanners);
+        assert_eq!(alignment.points().len(), world.len());
+        assert_eq!(alignment.max_manhattan_distance(), 1200 + 980 + 110);
+        assert!(align_all(&clouds, 5).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Searches over graphs described by a successor function rather than stored explicitly.
+//!
+//! Nodes are typically whole puzzle states, with the successor function producing every edge out
+//! of a node together with the node it leads to and, for weighted searches, its cost.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Edge costs of a weighted search.
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! define_cost {
+    ($($T:ty),*) => {
+        $(
+            impl Cost for $T {
+                const ZERO: Self = 0;
+            }
+        )*
+    };
+}
+
+define_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A path found by a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, E, C> {
+    /// The total cost of every edge taken.
+    pub cost: C,
+    pub start: N,
+    /// Every edge taken together with the node it leads to.
+    pub steps: Vec<(E, N)>,
+}
+
+impl<N, E, C> Path<N, E, C> {
+    /// The node the path ends at, a goal node.
+    pub fn end(&self) -> &N {
+        self.steps.last().map(|(_, n)| n).unwrap_or(&self.start)
+    }
+
+    /// Every node along the path, starting with `start`.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        std::iter::once(&self.start).chain(self.steps.iter().map(|(_, n)| n))
+    }
+}
+
+#[derive(Debug)]
+struct HeapEntry<N, C> {
+    node: N,
+    cost: C,
+    priority: C,
+}
+
+impl<N, C: Ord> Ord for HeapEntry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lowest priority first, preferring the node furthest along when tied
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<N, C: Ord> PartialOrd for HeapEntry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for HeapEntry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for HeapEntry<N, C> {}
+
+fn reconstruct_path<N: Eq + Hash, E, C>(
+    mut came_from: HashMap<N, (E, N)>,
+    end: N,
+    cost: C,
+) -> Path<N, E, C> {
+    let mut steps = vec![];
+    let mut current = end;
+
+    while let Some((edge, previous)) = came_from.remove(&current) {
+        steps.push((edge, current));
+        current = previous;
+    }
+    steps.reverse();
+
+    Path {
+        cost,
+        start: current,
+        steps,
+    }
+}
+
+/// Find the cheapest path from `start` to a node where `is_goal` holds.
+pub fn dijkstra<N, E, C, FN, IN, FG>(start: N, successors: FN, is_goal: FG) -> Option<Path<N, E, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (E, N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::ZERO, is_goal)
+}
+
+/// Find the cheapest path from `start` to a node where `is_goal` holds, exploring nodes in order
+/// of their cost so far plus `heuristic`.
+///
+/// The heuristic must never overestimate the remaining cost, and must not drop by more than the
+/// cost of any edge, for the path found to be the cheapest.
+pub fn astar<N, E, C, FN, IN, FH, FG>(
+    start: N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+) -> Option<Path<N, E, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (E, N, C)>,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    let mut distance = HashMap::from([(start.clone(), C::ZERO)]);
+    let mut came_from = HashMap::new();
+    let mut open = BinaryHeap::from([HeapEntry {
+        priority: heuristic(&start),
+        node: start,
+        cost: C::ZERO,
+    }]);
+
+    while let Some(HeapEntry { node, cost, .. }) = open.pop() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(came_from, node, cost));
+        }
+
+        if cost > distance[&node] {
+            // A cheaper way here was found after this entry was queued
+            continue;
+        }
+
+        for (edge, next, edge_cost) in successors(&node) {
+            let alt = cost + edge_cost;
+
+            if distance.get(&next).is_none_or(|d| alt < *d) {
+                distance.insert(next.clone(), alt);
+                came_from.insert(next.clone(), (edge, node.clone()));
+                open.push(HeapEntry {
+                    priority: alt + heuristic(&next),
+                    node: next,
+                    cost: alt,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the path from `start` to a node where `is_goal` holds with the fewest edges. Returns the
+/// goal reached that way, with the number of edges as the path's cost.
+pub fn bfs<N, E, FN, IN, FG>(
+    start: N,
+    mut successors: FN,
+    mut is_goal: FG,
+) -> Option<Path<N, E, usize>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (E, N)>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut came_from = HashMap::new();
+    let mut open = VecDeque::from([(start.clone(), 0)]);
+    let mut seen = HashSet::from([start]);
+
+    while let Some((node, length)) = open.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(came_from, node, length));
+        }
+
+        for (edge, next) in successors(&node) {
+            if seen.insert(next.clone()) {
+                came_from.insert(next.clone(), (edge, node.clone()));
+                open.push_back((next, length + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Count the distinct paths from `start` to nodes where `is_goal` holds, without continuing past
+/// a goal.
+///
+/// Every path is followed, so the nodes reachable from `start` must not form a cycle.
+pub fn count_paths<N, FN, IN, FG>(start: N, mut successors: FN, mut is_goal: FG) -> usize
+where
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut stack = vec![start];
+    let mut count = 0;
+
+    while let Some(node) = stack.pop() {
+        if is_goal(&node) {
+            count += 1;
+        } else {
+            stack.extend(successors(&node));
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, count_paths, dijkstra};
+    use crate::math::{Grid, Neighborhood, Vector2};
+
+    const GRID: &str = "\
+1163
+1381
+2136
+3694";
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let grid = Grid::parse_digits(GRID).unwrap();
+        let goal = Vector2::new(3, 3);
+        let successors = |p: &Vector2<usize>| {
+            grid.neighbors(*p, Neighborhood::Four)
+                .map(|n| ((), n, grid[n] as u32))
+                .collect::<Vec<_>>()
+        };
+
+        let path = dijkstra(Vector2::new(0, 0), successors, |p| *p == goal).unwrap();
+        assert_eq!(path.cost, 17);
+        assert_eq!(path.end(), &goal);
+        assert_eq!(path.nodes().count(), 7);
+        assert_eq!(
+            path.nodes().skip(1).map(|p| grid[*p] as u32).sum::<u32>(),
+            path.cost
+        );
+
+        let manhattan = |p: &Vector2<usize>| (goal.x - p.x + goal.y - p.y) as u32;
+        let guided = astar(Vector2::new(0, 0), successors, manhattan, |p| *p == goal).unwrap();
+        assert_eq!(guided.cost, path.cost);
+
+        let start = dijkstra(goal, successors, |p| *p == goal).unwrap();
+        assert_eq!((start.cost, start.steps.len()), (0, 0));
+
+        assert!(dijkstra(0u8, |_| vec![((), 1u8, 1u32)], |n| *n == 2).is_none());
+    }
+
+    #[test]
+    fn test_bfs() {
+        // Reach a number with the fewest doublings and increments
+        let successors = |n: &u32| {
+            [('*', n * 2), ('+', n + 1)]
+                .into_iter()
+                .filter(|(_, n)| *n <= 100)
+        };
+        let path = bfs(1, successors, |n| *n == 100).unwrap();
+
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.end(), &100);
+        assert!(path
+            .nodes()
+            .zip(&path.steps)
+            .all(|(n, (op, next))| match op {
+                '*' => n * 2 == *next,
+                _ => n + 1 == *next,
+            }));
+        assert!(bfs(1, successors, |n| *n == 101).is_none());
+    }
+
+    #[test]
+    fn test_count_paths() {
+        // Monotonic lattice paths across a 3 by 2 grid
+        let successors = |p: &(u8, u8)| {
+            [(p.0 + 1, p.1), (p.0, p.1 + 1)]
+                .into_iter()
+                .filter(|p| p.0 <= 3 && p.1 <= 2)
+        };
+
+        assert_eq!(count_paths((0, 0), successors, |p| *p == (3, 2)), 10);
+    }
+}